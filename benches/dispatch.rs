@@ -0,0 +1,75 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use chip8::cpu::Cpu;
+use chip8::machine::MachineConfig;
+
+// 0x200: LD V0 0x01
+// 0x202: ADD V1 0x01
+// 0x204: ADD V0 V1
+// 0x206: SE V0 0x00
+// 0x208: LD I 0x300
+// 0x20A: ADD I V0
+// 0x20C: SHR V2
+// 0x20E: JP 0x200
+const ROM: [u8; 16] = [
+    0x60, 0x01, 0x71, 0x01, 0x80, 0x14, 0x30, 0x00, 0xA3, 0x00, 0xF0, 0x1E, 0x82, 0x06, 0x12, 0x00,
+];
+
+// 0x200: LD I 0x000
+// 0x202: DRW V0 V1 0xF
+// 0x204: ADD V0 0x03
+// 0x206: ADD V1 0x01
+// 0x208: JP 0x202
+const DRAW_ROM: [u8; 10] = [0xA0, 0x00, 0xD0, 0x1F, 0x70, 0x03, 0x71, 0x01, 0x12, 0x02];
+
+/// Time the closure over the given number of iterations after a warm up run
+fn bench(name: &str, iterations: u32, mut f: impl FnMut()) {
+    for _ in 0..iterations / 10 {
+        f();
+    }
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<12} {:>10.1} ns/iter ({} iterations)",
+        name,
+        elapsed.as_nanos() as f64 / iterations as f64,
+        iterations
+    );
+}
+
+fn main() {
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    bench("step", 10_000_000, || {
+        black_box(cpu.step().unwrap());
+    });
+
+    // The whole ROM loop is one block
+    let mut threaded = Cpu::new(&ROM, MachineConfig::default());
+    bench("run_block", 1_000_000, || {
+        black_box(threaded.run_block(u32::MAX, u32::MAX).unwrap());
+    });
+
+    // Every fourth instruction draws a 15 row sprite
+    let mut drawing = Cpu::new(&DRAW_ROM, MachineConfig::default());
+    bench("draw_step", 1_000_000, || {
+        black_box(drawing.step().unwrap());
+    });
+
+    bench("state", 1_000_000, || {
+        black_box(drawing.state());
+    });
+
+    bench("new", 100_000, || {
+        black_box(Cpu::new(black_box(&ROM), MachineConfig::default()));
+    });
+
+    bench("clone", 100_000, || {
+        black_box(cpu.clone());
+    });
+}
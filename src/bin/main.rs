@@ -3,16 +3,69 @@ extern crate sdl2;
 use std::env::args;
 use std::fs;
 use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use chip8::gui::{events::Events, renderer::Renderer, window::Window};
+use chip8::cpu::Engine;
+use chip8::gui::{audio::Audio, events::Events, renderer::Renderer, window::Window};
+use chip8::platform::Platform;
+use chip8::timing::TimingMode;
 use chip8::Chip8;
 
-fn read_rom(args: Vec<String>) -> Result<Vec<u8>, String> {
-    if args.len() != 2 {
-        return Err(String::from("Invalid amount of arguments"));
+const USAGE: &str = "Usage: chip8-emulator <ROM> [--load-address <ADDRESS>] [--platform <PLATFORM>] [--vip-timing] [--threaded] [--vsync]
+Platforms: cowgod (default), vip, hires, chip8e, chip48, schip10, schip11, modern, xochip, megachip, chip8x";
+
+struct Options {
+    rom_path: String,
+    load_address: Option<u16>,
+    platform: Platform,
+    vip_timing: bool,
+    threaded: bool,
+    vsync: bool,
+}
+
+fn parse_address(address: &str) -> Result<u16, String> {
+    let digits = address.trim_start_matches("0x").trim_start_matches("0X");
+    u16::from_str_radix(digits, 16).map_err(|_| format!("Invalid address: {}", address))
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut rom_path = None;
+    let mut load_address = None;
+    let mut platform = Platform::default();
+    let mut vip_timing = false;
+    let mut threaded = false;
+    let mut vsync = false;
+
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--load-address" => {
+                let address = args.next().ok_or("Missing value for --load-address")?;
+                load_address = Some(parse_address(&address)?);
+            }
+            "--platform" => {
+                let name = args.next().ok_or("Missing value for --platform")?;
+                platform = name.parse()?;
+            }
+            "--vip-timing" => vip_timing = true,
+            "--threaded" => threaded = true,
+            "--vsync" => vsync = true,
+            _ if rom_path.is_none() => rom_path = Some(arg),
+            _ => return Err(String::from("Invalid amount of arguments")),
+        }
     }
 
-    let rom_path = &args[1];
+    Ok(Options {
+        rom_path: rom_path.ok_or("Invalid amount of arguments")?,
+        load_address,
+        platform,
+        vip_timing,
+        threaded,
+        vsync,
+    })
+}
+
+fn read_rom(rom_path: &str) -> Result<Vec<u8>, String> {
     let rom = fs::read(rom_path);
 
     match rom {
@@ -24,19 +77,46 @@ fn read_rom(args: Vec<String>) -> Result<Vec<u8>, String> {
 pub fn main() {
     let args: Vec<String> = args().collect();
 
-    let rom: Vec<u8> = read_rom(args).unwrap_or_else(|err| {
+    let options = parse_args(args).unwrap_or_else(|err| {
+        println!("{}\n{}", err, USAGE);
+        process::exit(1);
+    });
+
+    let rom: Vec<u8> = read_rom(&options.rom_path).unwrap_or_else(|err| {
         println!("Error reading ROM: {}", err);
         process::exit(1);
     });
 
+    let load_address = options
+        .load_address
+        .unwrap_or_else(|| options.platform.program_start());
+    let mut chip8 = Chip8::load_rom(&rom, load_address, options.platform).unwrap_or_else(|err| {
+        println!("Error loading ROM: {}", err);
+        process::exit(1);
+    });
+
+    // Runs are reproducible with the default seed, vary RND between runs instead
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.subsec_nanos())
+        .unwrap_or_default();
+    chip8.seed_rng(seed);
+
+    if options.vip_timing {
+        chip8.set_timing(TimingMode::CycleAccurate);
+    }
+
+    if options.threaded {
+        chip8.set_engine(Engine::Threaded);
+    }
+
     let sdl_context = sdl2::init().unwrap();
-    let window = Window::new(&sdl_context);
+    let window = Window::new(&sdl_context, chip8.display_width(), chip8.display_height());
 
-    let mut chip8 = Chip8::new(&rom);
-    let mut renderer = Renderer::new(window);
+    let mut renderer = Renderer::new(window, options.vsync);
     let mut events = Events::new(&sdl_context);
+    let mut audio = Audio::new(&sdl_context);
 
     renderer.clear_screen();
-    events.handle_events(&mut chip8, &mut renderer);
-
+    events.handle_events(&mut chip8, &mut renderer, &mut audio);
 }
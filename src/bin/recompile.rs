@@ -0,0 +1,83 @@
+use std::env::args;
+use std::fs;
+use std::process;
+
+use chip8::platform::Platform;
+use chip8::Chip8;
+
+const USAGE: &str = "Usage: chip8-recompile <ROM> [--load-address <ADDRESS>] [--platform <PLATFORM>] [--output <FILE>]
+Translates the ROM to a Rust module, printed unless written to the output file";
+
+struct Options {
+    rom_path: String,
+    load_address: Option<u16>,
+    platform: Platform,
+    output: Option<String>,
+}
+
+fn parse_address(address: &str) -> Result<u16, String> {
+    let digits = address.trim_start_matches("0x").trim_start_matches("0X");
+    u16::from_str_radix(digits, 16).map_err(|_| format!("Invalid address: {}", address))
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut rom_path = None;
+    let mut load_address = None;
+    let mut platform = Platform::default();
+    let mut output = None;
+
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--load-address" => {
+                let address = args.next().ok_or("Missing value for --load-address")?;
+                load_address = Some(parse_address(&address)?);
+            }
+            "--platform" => {
+                let name = args.next().ok_or("Missing value for --platform")?;
+                platform = name.parse()?;
+            }
+            "--output" => output = Some(args.next().ok_or("Missing value for --output")?),
+            _ if rom_path.is_none() => rom_path = Some(arg),
+            _ => return Err(String::from("Invalid amount of arguments")),
+        }
+    }
+
+    Ok(Options {
+        rom_path: rom_path.ok_or("Invalid amount of arguments")?,
+        load_address,
+        platform,
+        output,
+    })
+}
+
+pub fn main() {
+    let args: Vec<String> = args().collect();
+
+    let options = parse_args(args).unwrap_or_else(|err| {
+        println!("{}\n{}", err, USAGE);
+        process::exit(1);
+    });
+
+    let rom = fs::read(&options.rom_path).unwrap_or_else(|err| {
+        println!("Error reading ROM: {}", err);
+        process::exit(1);
+    });
+
+    let load_address = options
+        .load_address
+        .unwrap_or_else(|| options.platform.program_start());
+    let chip8 = Chip8::load_rom(&rom, load_address, options.platform).unwrap_or_else(|err| {
+        println!("Error loading ROM: {}", err);
+        process::exit(1);
+    });
+
+    let source = chip8.cpu().recompile();
+    match options.output {
+        Some(path) => fs::write(&path, source).unwrap_or_else(|err| {
+            println!("Error writing {}: {}", path, err);
+            process::exit(1);
+        }),
+        None => print!("{}", source),
+    }
+}
@@ -0,0 +1,291 @@
+/// Memory and I/O seen by the CDP1802
+pub trait Bus {
+    fn read(&mut self, address: u16) -> u8;
+    fn write(&mut self, address: u16, value: u8);
+
+    /// Byte put on the data bus by OUT 1 to OUT 7
+    fn output(&mut self, _port: u8, _value: u8) {}
+
+    /// Byte read from the data bus by INP 1 to INP 7
+    fn input(&mut self, _port: u8) -> u8 {
+        0
+    }
+
+    /// State of external flag EF1 to EF4
+    fn flag(&self, _flag: u8) -> bool {
+        false
+    }
+}
+
+/// RAM mirrored over the whole address space, with no I/O devices attached
+impl Bus for [u8] {
+    fn read(&mut self, address: u16) -> u8 {
+        self[address as usize % self.len()]
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        let len = self.len();
+        self[address as usize % len] = value;
+    }
+}
+
+/// RCA CDP1802 COSMAC microprocessor
+///
+/// Any of the sixteen 16-bit scratchpad registers can be the program counter,
+/// selected by P, or the data pointer, selected by X.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cdp1802 {
+    /// Scratchpad registers R0 to RF
+    pub r: [u16; 16],
+    /// Program counter designator
+    pub p: u8,
+    /// Data pointer designator
+    pub x: u8,
+    /// Data register, the accumulator
+    pub d: u8,
+    /// Data flag, the carry out of arithmetic and shifts
+    pub df: bool,
+    /// X and P saved on interrupt
+    pub t: u8,
+    /// Interrupt enable
+    pub ie: bool,
+    /// Output flip-flop, drives the VIP speaker
+    pub q: bool,
+    /// Stopped by IDL until the next interrupt or DMA cycle
+    pub idle: bool,
+}
+
+impl Cdp1802 {
+    /// CPU after a reset: R0 is the program counter and data pointer and
+    /// interrupts are enabled
+    pub fn new() -> Cdp1802 {
+        Cdp1802 {
+            ie: true,
+            ..Cdp1802::default()
+        }
+    }
+
+    /// Execute one instruction, returns the number of machine cycles it took
+    ///
+    /// While idle no instruction is executed and a single cycle passes.
+    pub fn step<B: Bus + ?Sized>(&mut self, bus: &mut B) -> u32 {
+        if self.idle {
+            return 1;
+        }
+
+        let opcode = self.fetch(bus);
+        let n = opcode & 0xF;
+        let ni = n as usize;
+        let xi = self.x as usize;
+
+        match opcode >> 4 {
+            0x0 if n == 0 => self.idle = true,
+            0x0 => self.d = bus.read(self.r[ni]),
+            0x1 => self.r[ni] = self.r[ni].wrapping_add(1),
+            0x2 => self.r[ni] = self.r[ni].wrapping_sub(1),
+            0x3 => {
+                // Short branches stay in the page of the target byte, 38 never branches
+                if self.condition(bus, n & 0x7) != (n & 0x8 != 0) {
+                    let pc = &mut self.r[self.p as usize];
+                    *pc = (*pc & 0xFF00) | bus.read(*pc) as u16;
+                } else {
+                    self.advance(1);
+                }
+            }
+            0x4 => {
+                self.d = bus.read(self.r[ni]);
+                self.r[ni] = self.r[ni].wrapping_add(1);
+            }
+            0x5 => bus.write(self.r[ni], self.d),
+            0x6 if n == 0 => self.r[xi] = self.r[xi].wrapping_add(1),
+            0x6 if n < 0x8 => {
+                let value = bus.read(self.r[xi]);
+                bus.output(n, value);
+                self.r[xi] = self.r[xi].wrapping_add(1);
+            }
+            0x6 => {
+                let value = bus.input(n & 0x7);
+                bus.write(self.r[xi], value);
+                self.d = value;
+            }
+            0x7 => self.execute_control(bus, n),
+            0x8 => self.d = self.r[ni] as u8,
+            0x9 => self.d = (self.r[ni] >> 8) as u8,
+            0xA => self.r[ni] = (self.r[ni] & 0xFF00) | self.d as u16,
+            0xB => self.r[ni] = (self.r[ni] & 0x00FF) | ((self.d as u16) << 8),
+            0xC => {
+                self.execute_long(bus, n);
+                return 3;
+            }
+            0xD => self.p = n,
+            0xE => self.x = n,
+            _ => self.execute_alu(bus, n),
+        }
+
+        2
+    }
+
+    /// Acknowledge an interrupt request, ignored while interrupts are disabled
+    pub fn interrupt(&mut self) {
+        if !self.ie {
+            return;
+        }
+
+        self.t = (self.x << 4) | self.p;
+        self.x = 2;
+        self.p = 1;
+        self.ie = false;
+        self.idle = false;
+    }
+
+    /// DMA out cycle, reads the byte R0 points to and advances R0
+    pub fn dma_out<B: Bus + ?Sized>(&mut self, bus: &mut B) -> u8 {
+        let value = bus.read(self.r[0]);
+        self.r[0] = self.r[0].wrapping_add(1);
+        self.idle = false;
+        value
+    }
+
+    /// Read the byte at the program counter and advance it
+    fn fetch<B: Bus + ?Sized>(&mut self, bus: &mut B) -> u8 {
+        let value = bus.read(self.r[self.p as usize]);
+        self.advance(1);
+        value
+    }
+
+    fn advance(&mut self, bytes: u16) {
+        let pc = &mut self.r[self.p as usize];
+        *pc = pc.wrapping_add(bytes);
+    }
+
+    /// Branch condition selected by the low three bits of a branch opcode
+    fn condition<B: Bus + ?Sized>(&self, bus: &B, condition: u8) -> bool {
+        match condition {
+            0x0 => true,
+            0x1 => self.q,
+            0x2 => self.d == 0,
+            0x3 => self.df,
+            flag => bus.flag(flag - 3),
+        }
+    }
+
+    /// 7N: returns, stack operations, carry arithmetic and Q
+    fn execute_control<B: Bus + ?Sized>(&mut self, bus: &mut B, n: u8) {
+        let xi = self.x as usize;
+        match n {
+            0x0 | 0x1 => {
+                let value = bus.read(self.r[xi]);
+                self.r[xi] = self.r[xi].wrapping_add(1);
+                self.x = value >> 4;
+                self.p = value & 0xF;
+                self.ie = n == 0x0;
+            }
+            0x2 => {
+                self.d = bus.read(self.r[xi]);
+                self.r[xi] = self.r[xi].wrapping_add(1);
+            }
+            0x3 => {
+                bus.write(self.r[xi], self.d);
+                self.r[xi] = self.r[xi].wrapping_sub(1);
+            }
+            0x6 => {
+                let carry = self.df as u8;
+                self.df = self.d & 0x1 != 0;
+                self.d = (self.d >> 1) | (carry << 7);
+            }
+            0x8 => bus.write(self.r[xi], self.t),
+            0x9 => {
+                self.t = (self.x << 4) | self.p;
+                bus.write(self.r[2], self.t);
+                self.x = self.p;
+                self.r[2] = self.r[2].wrapping_sub(1);
+            }
+            0xA => self.q = false,
+            0xB => self.q = true,
+            0xE => {
+                let carry = self.df as u8;
+                self.df = self.d & 0x80 != 0;
+                self.d = (self.d << 1) | carry;
+            }
+            // Arithmetic with carry, 74 to 77 on M(R(X)) and 7C to 7F immediate
+            _ => {
+                let operand = if n & 0x8 == 0 {
+                    bus.read(self.r[xi])
+                } else {
+                    self.fetch(bus)
+                };
+                self.arithmetic(n & 0x3, operand, self.df);
+            }
+        }
+    }
+
+    /// CN: long branches and skips
+    fn execute_long<B: Bus + ?Sized>(&mut self, bus: &mut B, n: u8) {
+        let condition = match n & 0x3 {
+            0x0 if n & 0x8 == 0 => true,
+            0x0 => self.ie,
+            0x1 => self.q,
+            0x2 => self.d == 0,
+            _ => self.df,
+        };
+
+        match n {
+            // NOP
+            0x4 => {}
+            // Long branches, C0 to C3 and the inverted C8 to CB
+            0x0..=0x3 | 0x9..=0xB => {
+                if condition != (n & 0x8 != 0) {
+                    let high = self.fetch(bus);
+                    let low = self.fetch(bus);
+                    self.r[self.p as usize] = u16::from_be_bytes([high, low]);
+                } else {
+                    self.advance(2);
+                }
+            }
+            // LSKP
+            0x8 => self.advance(2),
+            // Long skips, C5 to C7 and the inverted CC to CF
+            _ => {
+                if condition != (n & 0x8 == 0) {
+                    self.advance(2);
+                }
+            }
+        }
+    }
+
+    /// FN: logic and arithmetic on M(R(X)), or the immediate byte for F8 to FF
+    fn execute_alu<B: Bus + ?Sized>(&mut self, bus: &mut B, n: u8) {
+        if n == 0x6 || n == 0xE {
+            self.df = self.d & if n == 0x6 { 0x01 } else { 0x80 } != 0;
+            self.d = if n == 0x6 { self.d >> 1 } else { self.d << 1 };
+            return;
+        }
+
+        let operand = if n & 0x8 == 0 {
+            bus.read(self.r[self.x as usize])
+        } else {
+            self.fetch(bus)
+        };
+
+        match n & 0x7 {
+            0x0 => self.d = operand,
+            0x1 => self.d |= operand,
+            0x2 => self.d &= operand,
+            0x3 => self.d ^= operand,
+            op => self.arithmetic(op & 0x3, operand, op != 0x4),
+        }
+    }
+
+    /// Add (0), subtract D from the operand (1) or the operand from D (3),
+    /// with DF set on carry or cleared on borrow
+    fn arithmetic(&mut self, op: u8, operand: u8, carry: bool) {
+        let (a, b) = match op {
+            0x0 => (self.d as u16, operand as u16),
+            0x1 => (operand as u16, !self.d as u16),
+            _ => (self.d as u16, !operand as u16),
+        };
+        let result = a + b + carry as u16;
+        self.d = result as u8;
+        self.df = result > 0xFF;
+    }
+}
@@ -0,0 +1,86 @@
+use std::hint::spin_loop;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Time before a deadline at which sleeping stops and spinning starts, covering OS timer slack
+const SPIN_THRESHOLD: Duration = Duration::from_millis(2);
+
+/// Source of time the emulator is scheduled against
+pub trait Clock {
+    /// Time elapsed since the clock started
+    fn now(&self) -> Duration;
+
+    /// Block until the clock reaches the time, returns at once if it has
+    fn wait_until(&mut self, time: Duration);
+}
+
+/// Host monotonic time, for running in real time
+pub struct RealTimeClock {
+    start: Instant,
+}
+
+impl RealTimeClock {
+    pub fn new() -> RealTimeClock {
+        RealTimeClock {
+            start: Instant::now(),
+        }
+    }
+
+    /// Leave time out of the clock, such as time the host spent stalled
+    pub fn skip(&mut self, duration: Duration) {
+        self.start += duration;
+    }
+}
+
+impl Default for RealTimeClock {
+    fn default() -> RealTimeClock {
+        RealTimeClock::new()
+    }
+}
+
+impl Clock for RealTimeClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Sleep until shortly before the time and spin for the rest, which is
+    /// far more precise than sleeping alone
+    fn wait_until(&mut self, time: Duration) {
+        let deadline = self.start + time;
+        if let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            if remaining > SPIN_THRESHOLD {
+                sleep(remaining - SPIN_THRESHOLD);
+            }
+            while Instant::now() < deadline {
+                spin_loop();
+            }
+        }
+    }
+}
+
+/// Manually advanced time, for deterministic tests and headless runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VirtualClock {
+    now: Duration,
+}
+
+impl VirtualClock {
+    pub fn new() -> VirtualClock {
+        VirtualClock::default()
+    }
+
+    pub fn advance(&mut self, duration: Duration) {
+        self.now += duration;
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Jump ahead to the time instead of waiting for it
+    fn wait_until(&mut self, time: Duration) {
+        self.now = self.now.max(time);
+    }
+}
@@ -1,11 +1,47 @@
 // CPU Constants
 pub const MEMORY_SIZE: usize = 4096;
+pub const XO_MEMORY_SIZE: usize = 65536;
+pub const MEGA_MEMORY_SIZE: usize = 0x1000000;
 pub const GP_REGISTER_SIZE: usize = 16;
 pub const STACK_SIZE: usize = 16;
 pub const KEYPAD_SIZE: usize = 16;
 pub const PROGRAM_START: u16 = 0x200;
+pub const RPL_FLAG_COUNT: usize = 16;
+pub const HIRES_BOOT_JUMP: [u8; 2] = [0x12, 0x60];
+pub const HIRES_PROGRAM_START: u16 = 0x2C0;
+// COSMAC VIP memory layout, as offsets from the end of RAM
+pub const VIP_STACK_OFFSET: usize = 0x160;
+pub const VIP_REGISTER_OFFSET: usize = 0x110;
+pub const VIP_DISPLAY_OFFSET: usize = 0x100;
+pub const VIP_STACK_POINTER_OFFSET: usize = 0x131;
+pub const SYS_INSTRUCTION_LIMIT: usize = 100_000;
+// COSMAC VIP hardware, timed in CDP1802 machine cycles and CDP1861 scanlines
+pub const VIP_INTERPRETER_SIZE: usize = 0x200;
+pub const VIP_LINE_CYCLES: i32 = 14;
+pub const VIP_FRAME_LINES: usize = 262;
+pub const PIXIE_WIDTH: usize = 64;
+pub const PIXIE_HEIGHT: usize = 128;
+pub const PIXIE_FIRST_LINE: usize = 64;
+pub const PIXIE_INTERRUPT_LINES: usize = 2;
+pub const PIXIE_FLAG_LINES: usize = 4;
+// Timer Constants
+pub const TIMER_FREQUENCY: u32 = 60;
+pub const MAX_CATCH_UP_FRAMES: u32 = 5;
+// Audio Constants
+pub const AUDIO_PATTERN_SIZE: usize = 16;
+pub const SAMPLE_HEADER_SIZE: usize = 6;
+pub const DEFAULT_PITCH: u8 = 64;
+pub const AUDIO_SAMPLE_RATE: i32 = 44100;
+pub const BUZZER_FREQUENCY: f32 = 440.0;
+pub const BUZZER_VOLUME: f32 = 0.25;
 // Display Constants
 pub const DISPLAY_HEIGHT: usize = 32;
 pub const DISPLAY_WIDTH: usize = 64;
+pub const HIRES_DISPLAY_HEIGHT: usize = 64;
+pub const HIRES_DISPLAY_WIDTH: usize = 128;
+pub const TWO_PAGE_DISPLAY_HEIGHT: usize = 64;
+pub const MEGA_DISPLAY_HEIGHT: usize = 192;
+pub const MEGA_DISPLAY_WIDTH: usize = 256;
+pub const PALETTE_SIZE: usize = 256;
 pub const DISPLAY_SCALE: usize = 10;
 pub const WINDOW_TITLE: &str = "CHIP-8";
@@ -1,208 +1,466 @@
-use std::collections::HashMap;
-
+mod blocks;
+mod decode_cache;
 mod handlers;
 mod instructions;
+mod layout;
+mod recompiler;
+mod rng;
+mod stack;
+mod timing;
 
 use super::constants;
-use super::state::State;
+use super::display::{BlendMode, ColorDisplay, ColorZones, Display};
+use super::error::Chip8Error;
+use super::font::{FontSet, BIG_FONT, BIG_FONT_ADDRESS};
+use super::machine::{MachineConfig, MemoryLayout};
+use super::quirks::Quirks;
+use super::state::{Sample, SoundEvent, State};
+use blocks::BlockCache;
+pub use blocks::{BlockRun, Engine, NativeProgram};
+use decode_cache::DecodeCache;
+pub use instructions::InstructionSet;
 use instructions::{Instruction, InstructionType};
+use rng::Rng;
+use stack::Stack;
+pub use stack::StackPolicy;
+
+/// Instruction handler executing a decoded instruction
+type Handler = fn(&mut Cpu, Instruction) -> Result<(), Chip8Error>;
+
+/// Progress of a LD VX, K instruction waiting for a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct KeyWait {
+    /// Register the key value is stored to
+    register: u8,
+    /// Key that was pressed and must be released to complete the wait
+    key: Option<u8>,
+}
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Cpu {
-    pub memory: [u8; constants::MEMORY_SIZE],
+    pub memory: Vec<u8>,
+    memory_layout: MemoryLayout,
     pub gp_reg: [u8; constants::GP_REGISTER_SIZE],
     pub pc: u16,
-    pub sp: u16,
-    pub stack: [u16; constants::STACK_SIZE],
+    stack: Stack,
 
-    pub i_reg: u16,
+    pub i_reg: u32,
 
     // Timers decremented at frequency of 60 Hz (60 timers per second)
     pub delay_timer: u8,
     pub sound_timer: u8,
+    sound_playing: bool,
 
     pub keypad: [u8; constants::KEYPAD_SIZE],
+    pub keypad2: [u8; constants::KEYPAD_SIZE],
+    key_wait: Option<KeyWait>,
 
-    pub display_buffer: [[u8; constants::DISPLAY_HEIGHT]; constants::DISPLAY_WIDTH],
+    pub display_buffer: Display,
+    /// Display size outside SUPER-CHIP high resolution mode
+    display_width: usize,
+    display_height: usize,
     pub draw_flag: bool,
+    vblank_wait: bool,
+
+    // SUPER-CHIP flag registers, persisted in the HP 48 RPL user flags
+    pub rpl_flags: [u8; constants::RPL_FLAG_COUNT],
+    halted: bool,
+
+    // XO-CHIP display planes drawn to and audio registers
+    pub plane_mask: u8,
+    pub audio_pattern: Option<[u8; constants::AUDIO_PATTERN_SIZE]>,
+    pub pitch: u8,
+
+    // MegaChip colour display, drawing and sample registers
+    pub color_display: Option<ColorDisplay>,
+    pub palette: [[u8; 4]; constants::PALETTE_SIZE],
+    pub sprite_width: usize,
+    pub sprite_height: usize,
+    pub blend_mode: BlendMode,
+    pub collision_color: u8,
+    pub sample: Option<Sample>,
+
+    // CHIP-8X colour layer and I/O port latches
+    pub color_zones: ColorZones,
+    pub io_output: u8,
+    pub io_input: u8,
 
-    pub instructions: HashMap<InstructionType, fn(&mut Cpu, Instruction)>,
+    pub quirks: Quirks,
+    instruction_set: InstructionSet,
+    decode_cache: DecodeCache,
+    block_cache: BlockCache,
+
+    rng: Rng,
 }
 
 impl Cpu {
-    /// Initialize CPU with ROM
-    pub fn new(rom: &[u8]) -> Cpu {
-        let mut memory = [0; constants::MEMORY_SIZE];
-
-        // Load fontset
-        const FONT_SET: [u8; 80] = [
-            0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
-            0x20, 0x60, 0x20, 0x20, 0x70, // 1
-            0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
-            0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
-            0x90, 0x90, 0xF0, 0x10, 0x10, // 4
-            0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
-            0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
-            0xF0, 0x10, 0x20, 0x40, 0x40, // 7
-            0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
-            0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
-            0xF0, 0x90, 0xF0, 0x90, 0x90, // A
-            0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
-            0xF0, 0x80, 0x80, 0x80, 0xF0, // C
-            0xE0, 0x90, 0x90, 0x90, 0xE0, // D
-            0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
-            0xF0, 0x80, 0xF0, 0x80, 0x80, // F
-        ];
-
-        memory[0..FONT_SET.len()].copy_from_slice(&FONT_SET);
-
-        // Load ROM to program memory
-        let rom_start: usize = constants::PROGRAM_START as usize;
+    /// Initialize CPU for the configured machine with ROM loaded at the configured address
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ROM does not fit in memory, use [`Cpu::try_new`] to handle the error
+    pub fn new(rom: &[u8], config: MachineConfig) -> Cpu {
+        Cpu::try_new(rom, config).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Initialize CPU for the configured machine with ROM loaded at the
+    /// configured address, which is also the entry point
+    ///
+    /// With hi-res CHIP-8, a program starting with the 0x1260 boot jump starts
+    /// at 0x2C0 instead.
+    pub fn try_new(rom: &[u8], config: MachineConfig) -> Result<Cpu, Chip8Error> {
+        let MachineConfig {
+            memory_size,
+            load_address,
+            instruction_set,
+            display_width,
+            display_height,
+            memory_layout,
+            font,
+            ..
+        } = config;
+
+        let display_buffer = Display::try_new(display_width, display_height)?;
+
+        // Program memory ends where the VIP interpreter keeps its stack
+        let program_end = match memory_layout {
+            MemoryLayout::Separate => memory_size,
+            MemoryLayout::Vip => memory_size.saturating_sub(constants::VIP_STACK_OFFSET),
+        };
+
+        // Load ROM to program memory, it must not overlap the fontsets
+        let big_font_end = BIG_FONT_ADDRESS as usize + BIG_FONT.len();
+        let rom_start: usize = load_address as usize;
         let rom_end: usize = rom_start + rom.len();
 
+        if rom_start < big_font_end || rom_start >= program_end {
+            return Err(Chip8Error::InvalidLoadAddress {
+                address: load_address,
+            });
+        }
+
+        if rom_end > program_end {
+            return Err(Chip8Error::RomTooLarge {
+                address: load_address,
+                size: rom.len(),
+                max_size: program_end - rom_start,
+            });
+        }
+
+        let mut memory = vec![0; memory_size];
         memory[rom_start..rom_end].copy_from_slice(rom);
 
-        Cpu {
+        // Load fontsets
+        let font = font.data();
+        memory[0..font.len()].copy_from_slice(font);
+        memory[BIG_FONT_ADDRESS as usize..big_font_end].copy_from_slice(&BIG_FONT);
+
+        let boot_jump = instruction_set.has_hires()
+            && memory[rom_start..].starts_with(&constants::HIRES_BOOT_JUMP);
+        let pc = if boot_jump {
+            constants::HIRES_PROGRAM_START
+        } else {
+            load_address
+        };
+
+        let mut cpu = Cpu {
             memory,
+            memory_layout,
             gp_reg: [0; constants::GP_REGISTER_SIZE],
-            pc: constants::PROGRAM_START,
-            sp: 0,
-            stack: [0; constants::STACK_SIZE],
+            pc,
+            stack: Stack::new(config.stack_depth, config.stack_policy),
 
             i_reg: 0,
 
             delay_timer: 0,
             sound_timer: 0,
+            sound_playing: false,
 
             keypad: [0; constants::KEYPAD_SIZE],
+            keypad2: [0; constants::KEYPAD_SIZE],
+            key_wait: None,
 
-            display_buffer: [[0; constants::DISPLAY_HEIGHT]; constants::DISPLAY_WIDTH],
+            display_buffer,
+            display_width,
+            display_height,
             draw_flag: false,
+            vblank_wait: false,
 
-            instructions: HashMap::from([
-                (
-                    InstructionType::Ret,
-                    handlers::ret as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::Jmp,
-                    handlers::jmp as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::CallNnn,
-                    handlers::call_nnn as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::SeVxKk,
-                    handlers::se_vx_kk as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::SneVxKk,
-                    handlers::sne_vx_kk as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::SeVxVy,
-                    handlers::se_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::LdVxKk,
-                    handlers::ld_vx_kk as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::AddVxKk,
-                    handlers::add_vx_kk as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::LdVxVy,
-                    handlers::ld_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::OrVxVy,
-                    handlers::or_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::AndVxVy,
-                    handlers::and_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::XorVxVy,
-                    handlers::xor_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::AddVxVy,
-                    handlers::add_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::SubVxVy,
-                    handlers::sub_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::ShrVxVy,
-                    handlers::shr_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::SubnVxVy,
-                    handlers::subn_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::ShlVxVy,
-                    handlers::shl_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::SneVxVy,
-                    handlers::sne_vx_vy as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::LdINnn,
-                    handlers::ld_i_nnn as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::DrwVxVyN,
-                    handlers::drw_vx_vy_n as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::LdIVx,
-                    handlers::ld_i_vx as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::LdVxI,
-                    handlers::ld_vx_i as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-                (
-                    InstructionType::LdBVx,
-                    handlers::ld_b_vx as fn(cpu: &mut Cpu, instr: Instruction),
-                ),
-            ]),
-        }
+            rpl_flags: [0; constants::RPL_FLAG_COUNT],
+            halted: false,
+
+            plane_mask: 0x1,
+            audio_pattern: None,
+            pitch: constants::DEFAULT_PITCH,
+
+            color_display: None,
+            palette: [[0; 4]; constants::PALETTE_SIZE],
+            sprite_width: 0,
+            sprite_height: 0,
+            blend_mode: BlendMode::Normal,
+            collision_color: 0,
+            sample: None,
+
+            color_zones: ColorZones::new(display_width, display_height),
+            io_output: 0,
+            io_input: 0,
+
+            quirks: config.quirks,
+            instruction_set,
+            // pc only reaches the first 64K, and VIP mirrored state is never cached
+            decode_cache: DecodeCache::new(program_end.min(0x10000)),
+            block_cache: BlockCache::default(),
+
+            rng: Rng::new(config.rng_seed),
+        };
+        cpu.store_layout();
+
+        Ok(cpu)
+    }
+
+    /// Replace the hex digit sprites at the start of memory
+    pub fn load_font(&mut self, font_set: FontSet) {
+        let font = font_set.data();
+        self.memory[0..font.len()].copy_from_slice(font);
+        self.invalidate_code(0, font.len());
+    }
+
+    /// Instruction set decoded, from the machine configuration
+    pub fn instruction_set(&self) -> InstructionSet {
+        self.instruction_set
+    }
+
+    /// Decode a different instruction set from the next instruction on
+    pub fn set_instruction_set(&mut self, instruction_set: InstructionSet) {
+        self.instruction_set = instruction_set;
+        self.invalidate_decode_cache();
+    }
+
+    /// Forget decoded and compiled instructions, needed after writing to
+    /// `memory` directly
+    pub fn invalidate_decode_cache(&mut self) {
+        self.decode_cache.clear();
+        self.block_cache.clear();
+    }
+
+    /// Forget decoded and compiled instructions overlapping `len` bytes of
+    /// memory written from `start`
+    fn invalidate_code(&mut self, start: usize, len: usize) {
+        self.decode_cache.invalidate(start, len);
+        self.block_cache.invalidate(start, len);
+    }
+
+    /// Reseed the random number generator used by the RND instruction
+    pub fn seed_rng(&mut self, seed: u32) {
+        self.rng = Rng::new(seed);
+    }
+
+    /// Replace the call stack with an empty one of the given depth and overflow policy
+    pub fn configure_stack(&mut self, depth: usize, policy: StackPolicy) {
+        self.stack = Stack::new(depth, policy);
+    }
+
+    /// Return addresses on the call stack, oldest first
+    pub fn call_stack(&self) -> &[u16] {
+        self.stack.frames()
+    }
+
+    /// Maximum number of nested subroutine calls
+    pub fn stack_depth(&self) -> usize {
+        self.stack.depth()
     }
 
     /// Emulate clock cycle
-    pub fn run_cycle(&mut self) -> State {
-        // Fetch
-        let instr: Instruction = self.fetch();
-
-        // Decode
-        let instr_type: InstructionType = match self.decode(&instr) {
-            Some(v) => v,
-            None => panic!(
-                "Failed to decode instruction: 0x{:X} at address 0x{:X}",
-                instr.raw_instr, self.pc
-            ),
+    pub fn run_cycle(&mut self) -> Result<State, Chip8Error> {
+        self.step()?;
+        Ok(self.state())
+    }
+
+    /// Run one instruction, returns the COSMAC VIP machine cycles it took
+    ///
+    /// Returns 0 without running anything while execution is halted.
+    pub fn step(&mut self) -> Result<u32, Chip8Error> {
+        // Execution is halted while waiting for a key release or display refresh
+        if !self.is_running() {
+            return Ok(0);
+        }
+
+        // Fetch and decode, unless the instruction at pc was decoded before
+        let (instr, instr_type) = match self.decode_cache.get(self.pc) {
+            Some(decoded) => decoded,
+            None => {
+                let instr: Instruction = self.fetch()?;
+                let instr_type: InstructionType = match self.decode(&instr) {
+                    Some(v) => v,
+                    None => {
+                        return Err(Chip8Error::UnknownOpcode {
+                            address: self.pc,
+                            opcode: instr.raw_instr,
+                        })
+                    }
+                };
+                self.decode_cache.insert(self.pc, instr, instr_type);
+                (instr, instr_type)
+            }
         };
 
-        // Execute
-        self.execute(instr_type, instr);
+        let cycles = self.vip_cycles(&instr_type, &instr);
+
+        // Execute, keeping state mirrored in RAM up to date with the VIP memory layout
+        self.store_layout();
+        let writes_memory = layout::writes_memory(&instr_type);
+        self.execute(instr_type, instr)?;
+        if writes_memory {
+            self.load_layout();
+        } else {
+            self.store_layout();
+        }
+
+        Ok(cycles)
+    }
+
+    /// Snapshot of the display for the frontend, resetting the draw flag
+    pub fn state(&mut self) -> State {
+        let color_zones = self
+            .instruction_set
+            .has_chip8x()
+            .then_some(&self.color_zones);
+        State::new(
+            &mut self.draw_flag,
+            &self.display_buffer,
+            self.color_display.as_ref(),
+            color_zones,
+        )
+    }
+
+    /// Decrement delay and sound timers, called at 60 Hz of emulated time
+    ///
+    /// Returns a sound event when the buzzer should start or stop
+    pub fn tick_timers(&mut self) -> Option<SoundEvent> {
+        // Buzzer sounds for the tick if sound timer is still running
+        let sound_playing = self.sound_timer > 0;
+
+        // Timers tick on the display refresh, which releases a display wait
+        self.vblank_wait = false;
+
+        self.delay_timer = self.delay_timer.saturating_sub(1);
+        self.sound_timer = self.sound_timer.saturating_sub(1);
+
+        if sound_playing == self.sound_playing {
+            return None;
+        }
+
+        self.sound_playing = sound_playing;
+
+        if sound_playing {
+            Some(SoundEvent::Start)
+        } else {
+            Some(SoundEvent::Stop)
+        }
+    }
+
+    /// Whether the buzzer is currently sounding
+    pub fn is_sound_playing(&self) -> bool {
+        self.sound_playing
+    }
+
+    /// Width of the display outside SUPER-CHIP high resolution mode
+    pub fn display_width(&self) -> usize {
+        self.display_width
+    }
+
+    /// Height of the display outside SUPER-CHIP high resolution mode
+    pub fn display_height(&self) -> usize {
+        self.display_height
+    }
+
+    /// Change the display height outside SUPER-CHIP high resolution mode, clearing the display
+    pub fn set_display_height(&mut self, height: usize) {
+        self.display_height = height;
+        self.display_buffer
+            .set_resolution(self.display_width, height);
+        self.color_zones = ColorZones::new(self.display_width, height);
+        self.draw_flag = true;
+    }
+
+    /// Mark key as pressed
+    pub fn key_down(&mut self, key: u8) {
+        if key as usize >= constants::KEYPAD_SIZE {
+            return;
+        }
+
+        self.keypad[key as usize] = 1;
+
+        if let Some(key_wait) = &mut self.key_wait {
+            key_wait.key.get_or_insert(key);
+        }
+    }
+
+    /// Mark key as released, completing a key wait if it was on this key
+    pub fn key_up(&mut self, key: u8) {
+        if key as usize >= constants::KEYPAD_SIZE {
+            return;
+        }
+
+        self.keypad[key as usize] = 0;
+
+        if let Some(KeyWait {
+            register,
+            key: Some(pressed),
+        }) = self.key_wait
+        {
+            if pressed == key {
+                self.gp_reg[register as usize] = key;
+                self.key_wait = None;
+            }
+        }
+    }
+
+    /// Mark key on the CHIP-8X second keypad as pressed
+    pub fn key2_down(&mut self, key: u8) {
+        if let Some(state) = self.keypad2.get_mut(key as usize) {
+            *state = 1;
+        }
+    }
+
+    /// Mark key on the CHIP-8X second keypad as released
+    pub fn key2_up(&mut self, key: u8) {
+        if let Some(state) = self.keypad2.get_mut(key as usize) {
+            *state = 0;
+        }
+    }
 
-        // TODO: Handle timers
+    /// Whether execution is halted by LD VX, K until a key is pressed and released
+    pub fn is_waiting_for_key(&self) -> bool {
+        self.key_wait.is_some()
+    }
 
-        State::new(&mut self.draw_flag, self.display_buffer)
+    /// Whether the program stopped the interpreter with EXIT
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Whether instructions run, as opposed to being halted or waiting for a
+    /// key release or display refresh
+    pub fn is_running(&self) -> bool {
+        !self.halted && self.key_wait.is_none() && !self.vblank_wait
     }
 
     /// Fetch instruction from memory
-    fn fetch(&self) -> Instruction {
-        let instr: u16 = ((self.memory[(self.pc as usize)] as u16) << 8)
-            | (self.memory[((self.pc + 1) as usize)] as u16);
+    fn fetch(&self) -> Result<Instruction, Chip8Error> {
+        self.fetch_at(self.pc)
+    }
+
+    /// Fetch instruction from memory at the address
+    fn fetch_at(&self, address: u16) -> Result<Instruction, Chip8Error> {
+        if address as usize + 1 >= self.memory.len() {
+            return Err(Chip8Error::PcOutOfBounds { address });
+        }
 
-        Instruction {
+        let instr: u16 = ((self.memory[address as usize] as u16) << 8)
+            | (self.memory[address as usize + 1] as u16);
+
+        Ok(Instruction {
             raw_instr: instr,
             op: ((instr & 0xf000) >> 12) as u8,
             nnn: instr & 0xfff,
@@ -210,22 +468,58 @@ impl Cpu {
             x: ((instr & 0xf00) >> 8) as u8,
             y: ((instr & 0xf0) >> 4) as u8,
             kk: (instr & 0xff) as u8,
-        }
+        })
     }
 
     /// Decodes the instruction
     fn decode(&self, instr: &Instruction) -> Option<InstructionType> {
+        let mega_chip = self.instruction_set.has_mega_chip();
+
         match instr.op {
             0x0 => match instr.raw_instr {
                 0x00E0 => Some(InstructionType::Cls),
                 0x00EE => Some(InstructionType::Ret),
+                0x02A0 if self.instruction_set.has_chip8x() => Some(InstructionType::CycleBg),
+                0x0230 if self.instruction_set.has_hires() => Some(InstructionType::Cls),
+                0x00ED if self.instruction_set.has_chip8e() => Some(InstructionType::Exit),
+                0x0010 if mega_chip => Some(InstructionType::MegaOff),
+                0x0011 if mega_chip => Some(InstructionType::MegaOn),
+                0x0100..=0x01FF if mega_chip => Some(InstructionType::LdILong24),
+                0x0200..=0x02FF if mega_chip => Some(InstructionType::LdPal),
+                0x0300..=0x03FF if mega_chip => Some(InstructionType::SprW),
+                0x0400..=0x04FF if mega_chip => Some(InstructionType::SprH),
+                0x0600..=0x0601 if mega_chip => Some(InstructionType::PlaySample),
+                0x0700 if mega_chip => Some(InstructionType::StopSample),
+                0x0800..=0x0805 if mega_chip => Some(InstructionType::Blend),
+                0x0900..=0x09FF if mega_chip => Some(InstructionType::CollisionColor),
+                _ if self.instruction_set.has_super_chip() => match instr.raw_instr {
+                    0x00C0..=0x00CF => Some(InstructionType::ScdN),
+                    0x00D0..=0x00DF if self.instruction_set.has_xo_chip() => {
+                        Some(InstructionType::ScuN)
+                    }
+                    0x00FB => Some(InstructionType::Scr),
+                    0x00FC => Some(InstructionType::Scl),
+                    0x00FD => Some(InstructionType::Exit),
+                    0x00FE => Some(InstructionType::Low),
+                    0x00FF => Some(InstructionType::High),
+                    _ => None,
+                },
+                _ if self.memory_layout == MemoryLayout::Vip => Some(InstructionType::SysNnn),
                 _ => None,
             },
             0x1 => Some(InstructionType::Jmp),
             0x2 => Some(InstructionType::CallNnn),
             0x3 => Some(InstructionType::SeVxKk),
             0x4 => Some(InstructionType::SneVxKk),
-            0x5 => Some(InstructionType::SeVxVy),
+            0x5 => match instr.n {
+                0x1 if self.instruction_set.has_chip8x() => Some(InstructionType::AddNibVxVy),
+                0x1 if self.instruction_set.has_chip8e() => Some(InstructionType::SgtVxVy),
+                0x2 if self.instruction_set.has_chip8e() => Some(InstructionType::SaveVxVy),
+                0x3 if self.instruction_set.has_chip8e() => Some(InstructionType::LoadVxVy),
+                0x2 if self.instruction_set.has_xo_chip() => Some(InstructionType::SaveVxVy),
+                0x3 if self.instruction_set.has_xo_chip() => Some(InstructionType::LoadVxVy),
+                _ => Some(InstructionType::SeVxVy),
+            },
             0x6 => Some(InstructionType::LdVxKk),
             0x7 => Some(InstructionType::AddVxKk),
             0x8 => match instr.n {
@@ -242,14 +536,33 @@ impl Cpu {
             },
             0x9 => Some(InstructionType::SneVxVy),
             0xA => Some(InstructionType::LdINnn),
+            0xB if self.instruction_set.has_chip8x() => match instr.n {
+                0x0 => Some(InstructionType::ColZone),
+                _ => Some(InstructionType::ColArea),
+            },
+            0xB if self.instruction_set.has_chip8e() => match instr.x {
+                0xB => Some(InstructionType::BranchBack),
+                0xF => Some(InstructionType::BranchFwd),
+                _ => Some(InstructionType::JmpV0Nnn),
+            },
             0xB => Some(InstructionType::JmpV0Nnn),
             0xC => Some(InstructionType::RndVxKk),
             0xD => Some(InstructionType::DrwVxVyN),
             0xE => match instr.kk {
                 0x9E => Some(InstructionType::SkpVx),
                 0xA1 => Some(InstructionType::SkNpVx),
+                0xF2 if self.instruction_set.has_chip8x() => Some(InstructionType::Skp2Vx),
+                0xF5 if self.instruction_set.has_chip8x() => Some(InstructionType::SkNp2Vx),
                 _ => None,
             },
+            0xF if self.instruction_set.has_xo_chip() && instr.kk <= 0x02 => {
+                match (instr.x, instr.kk) {
+                    (0x0, 0x00) => Some(InstructionType::LdILong),
+                    (_, 0x01) => Some(InstructionType::PlaneN),
+                    (0x0, 0x02) => Some(InstructionType::AudioI),
+                    _ => None,
+                }
+            }
             0xF => match instr.kk {
                 0x07 => Some(InstructionType::LdVxDt),
                 0x0A => Some(InstructionType::LdVxK),
@@ -260,6 +573,16 @@ impl Cpu {
                 0x33 => Some(InstructionType::LdBVx),
                 0x55 => Some(InstructionType::LdIVx),
                 0x65 => Some(InstructionType::LdVxI),
+                0xF8 if self.instruction_set.has_chip8x() => Some(InstructionType::OutVx),
+                0xFB if self.instruction_set.has_chip8x() => Some(InstructionType::InVx),
+                0x03 if self.instruction_set.has_chip8e() => Some(InstructionType::OutVx),
+                _ if self.instruction_set.has_super_chip() => match instr.kk {
+                    0x30 => Some(InstructionType::LdHfVx),
+                    0x3A if self.instruction_set.has_xo_chip() => Some(InstructionType::PitchVx),
+                    0x75 => Some(InstructionType::LdRVx),
+                    0x85 => Some(InstructionType::LdVxR),
+                    _ => None,
+                },
                 _ => None,
             },
             _ => None,
@@ -267,13 +590,130 @@ impl Cpu {
     }
 
     /// Execute instruction
-    fn execute(&mut self, instr_type: InstructionType, instr: Instruction) {
-        match self.instructions.get(&instr_type) {
-            Some(v) => v(self, instr),
-            None => panic!(
-                "Cannot execute instruction: 0x{:X} at 0x{:X}",
-                instr.raw_instr, self.pc
-            ),
+    fn execute(
+        &mut self,
+        instr_type: InstructionType,
+        instr: Instruction,
+    ) -> Result<(), Chip8Error> {
+        Cpu::handler(instr_type)(self, instr)
+    }
+
+    /// Handler executing the instruction type
+    fn handler(instr_type: InstructionType) -> Handler {
+        match instr_type {
+            InstructionType::Cls => handlers::cls,
+            InstructionType::Ret => handlers::ret,
+            InstructionType::Jmp => handlers::jmp,
+            InstructionType::CallNnn => handlers::call_nnn,
+            InstructionType::SeVxKk => handlers::se_vx_kk,
+            InstructionType::SneVxKk => handlers::sne_vx_kk,
+            InstructionType::SeVxVy => handlers::se_vx_vy,
+            InstructionType::LdVxKk => handlers::ld_vx_kk,
+            InstructionType::AddVxKk => handlers::add_vx_kk,
+            InstructionType::LdVxVy => handlers::ld_vx_vy,
+            InstructionType::OrVxVy => handlers::or_vx_vy,
+            InstructionType::AndVxVy => handlers::and_vx_vy,
+            InstructionType::XorVxVy => handlers::xor_vx_vy,
+            InstructionType::AddVxVy => handlers::add_vx_vy,
+            InstructionType::SubVxVy => handlers::sub_vx_vy,
+            InstructionType::ShrVxVy => handlers::shr_vx_vy,
+            InstructionType::SubnVxVy => handlers::subn_vx_vy,
+            InstructionType::ShlVxVy => handlers::shl_vx_vy,
+            InstructionType::SneVxVy => handlers::sne_vx_vy,
+            InstructionType::LdINnn => handlers::ld_i_nnn,
+            InstructionType::JmpV0Nnn => handlers::jmp_v0_nnn,
+            InstructionType::RndVxKk => handlers::rnd_vx_kk,
+            InstructionType::DrwVxVyN => handlers::drw_vx_vy_n,
+            InstructionType::SkpVx => handlers::skp_vx,
+            InstructionType::SkNpVx => handlers::sknp_vx,
+            InstructionType::LdVxDt => handlers::ld_vx_dt,
+            InstructionType::LdVxK => handlers::ld_vx_k,
+            InstructionType::LdDtVx => handlers::ld_dt_vx,
+            InstructionType::LdStVx => handlers::ld_st_vx,
+            InstructionType::AddIVx => handlers::add_i_vx,
+            InstructionType::LdFVx => handlers::ld_f_vx,
+            InstructionType::LdIVx => handlers::ld_i_vx,
+            InstructionType::LdVxI => handlers::ld_vx_i,
+            InstructionType::LdBVx => handlers::ld_b_vx,
+            InstructionType::ScdN => handlers::scd_n,
+            InstructionType::Scr => handlers::scr,
+            InstructionType::Scl => handlers::scl,
+            InstructionType::Exit => handlers::exit,
+            InstructionType::Low => handlers::low,
+            InstructionType::High => handlers::high,
+            InstructionType::LdHfVx => handlers::ld_hf_vx,
+            InstructionType::LdRVx => handlers::ld_r_vx,
+            InstructionType::LdVxR => handlers::ld_vx_r,
+            InstructionType::ScuN => handlers::scu_n,
+            InstructionType::SaveVxVy => handlers::save_vx_vy,
+            InstructionType::LoadVxVy => handlers::load_vx_vy,
+            InstructionType::LdILong => handlers::ld_i_long,
+            InstructionType::PlaneN => handlers::plane_n,
+            InstructionType::AudioI => handlers::audio_i,
+            InstructionType::PitchVx => handlers::pitch_vx,
+            InstructionType::MegaOff => handlers::mega_off,
+            InstructionType::MegaOn => handlers::mega_on,
+            InstructionType::LdILong24 => handlers::ld_i_long_24,
+            InstructionType::LdPal => handlers::ld_pal,
+            InstructionType::SprW => handlers::spr_w,
+            InstructionType::SprH => handlers::spr_h,
+            InstructionType::PlaySample => handlers::play_sample,
+            InstructionType::StopSample => handlers::stop_sample,
+            InstructionType::Blend => handlers::blend,
+            InstructionType::CollisionColor => handlers::collision_color,
+            InstructionType::CycleBg => handlers::cycle_bg,
+            InstructionType::AddNibVxVy => handlers::add_nib_vx_vy,
+            InstructionType::ColZone => handlers::col_zone,
+            InstructionType::ColArea => handlers::col_area,
+            InstructionType::Skp2Vx => handlers::skp2_vx,
+            InstructionType::SkNp2Vx => handlers::sknp2_vx,
+            InstructionType::OutVx => handlers::out_vx,
+            InstructionType::InVx => handlers::in_vx,
+            InstructionType::SgtVxVy => handlers::sgt_vx_vy,
+            InstructionType::BranchBack => handlers::branch_back,
+            InstructionType::BranchFwd => handlers::branch_fwd,
+            InstructionType::SysNnn => handlers::sys_nnn,
+        }
+    }
+
+    /// Advance pc past the next instruction
+    ///
+    /// The four byte XO-CHIP F000 NNNN and MegaChip 01NN NNNN instructions
+    /// are skipped as a whole.
+    fn skip_next_instruction(&mut self) {
+        let next = self.pc.wrapping_add(2);
+        self.pc = next.wrapping_add(self.instruction_size(next));
+    }
+
+    /// Size in bytes of the instruction at the address, 4 for long loads
+    fn instruction_size(&self, address: u16) -> u16 {
+        let address = address as usize;
+        let long_load = match self.memory.get(address..address + 2) {
+            Some([0xF0, 0x00]) => self.instruction_set.has_xo_chip(),
+            Some([0x01, _]) => self.instruction_set.has_mega_chip(),
+            _ => false,
+        };
+        if long_load {
+            4
+        } else {
+            2
+        }
+    }
+
+    /// Check memory accessed by instruction lies within RAM
+    fn check_memory_range(
+        &self,
+        instr: &Instruction,
+        start: usize,
+        len: usize,
+    ) -> Result<(), Chip8Error> {
+        if start + len > self.memory.len() {
+            return Err(Chip8Error::MemoryOutOfBounds {
+                address: self.pc,
+                opcode: instr.raw_instr,
+                memory_address: start.max(self.memory.len()),
+            });
         }
+        Ok(())
     }
 }
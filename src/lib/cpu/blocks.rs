@@ -0,0 +1,230 @@
+use std::sync::Arc;
+
+use super::instructions::{Instruction, InstructionType};
+use super::{Cpu, Handler};
+use crate::error::Chip8Error;
+use crate::machine::MemoryLayout;
+
+/// Entry point of a program translated to Rust by [`Cpu::recompile`], with
+/// the same contract as [`Cpu::run_block`]
+pub type NativeProgram = fn(&mut Cpu, u32, u32) -> Result<BlockRun, Chip8Error>;
+
+/// Engine running CHIP-8 instructions
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Engine {
+    /// Fetch, decode and execute one instruction at a time
+    #[default]
+    Interpreter,
+    /// Run basic blocks translated to chains of handler calls, see [`Cpu::run_block`]
+    Threaded,
+    /// Run a program recompiled ahead of time, see [`Cpu::recompile`]
+    Native(NativeProgram),
+}
+
+/// Instructions and COSMAC VIP machine cycles run by [`Cpu::run_block`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BlockRun {
+    pub instructions: u32,
+    pub cycles: u32,
+}
+
+/// Straight-line run of instructions starting at one address
+struct Block {
+    /// First address after the block
+    end: usize,
+    /// Handlers of the instructions that only advance pc by 2, with their
+    /// operands and cycles
+    ops: Vec<(Handler, Instruction, u32)>,
+    /// Instruction ending the block, which may branch, skip, draw, wait or
+    /// write memory. Missing when the next instruction cannot be decoded.
+    exit: Option<(Instruction, InstructionType)>,
+}
+
+/// Compiled blocks keyed by their start address
+#[derive(Clone, Default)]
+pub struct BlockCache {
+    /// Allocated by the first compiled block, so interpreting CPUs stay cheap to create
+    blocks: Vec<Option<Arc<Block>>>,
+    /// Addresses of the compiled blocks
+    starts: Vec<u16>,
+}
+
+impl BlockCache {
+    /// Drop the blocks overlapping `len` bytes of memory from `start`
+    pub fn invalidate(&mut self, start: usize, len: usize) {
+        let end = start.saturating_add(len);
+        let blocks = &mut self.blocks;
+        self.starts.retain(|&address| {
+            let block = &mut blocks[address as usize];
+            let overlaps = block
+                .as_ref()
+                .is_some_and(|block| (address as usize) < end && start < block.end);
+            if overlaps {
+                *block = None;
+            }
+            !overlaps
+        });
+    }
+
+    pub fn clear(&mut self) {
+        for address in self.starts.drain(..) {
+            self.blocks[address as usize] = None;
+        }
+    }
+}
+
+impl std::fmt::Debug for BlockCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("BlockCache")
+            .field("starts", &self.starts)
+            .finish()
+    }
+}
+
+/// Compiled blocks are derived from memory, they don't make two CPUs differ
+impl PartialEq for BlockCache {
+    fn eq(&self, _other: &BlockCache) -> bool {
+        true
+    }
+}
+
+/// Whether the instruction only advances pc by 2, without halting, drawing
+/// or writing memory
+pub(super) fn is_straight(instr_type: InstructionType) -> bool {
+    matches!(
+        instr_type,
+        InstructionType::LdVxKk
+            | InstructionType::AddVxKk
+            | InstructionType::LdVxVy
+            | InstructionType::OrVxVy
+            | InstructionType::AndVxVy
+            | InstructionType::XorVxVy
+            | InstructionType::AddVxVy
+            | InstructionType::SubVxVy
+            | InstructionType::ShrVxVy
+            | InstructionType::SubnVxVy
+            | InstructionType::ShlVxVy
+            | InstructionType::LdINnn
+            | InstructionType::RndVxKk
+            | InstructionType::LdVxDt
+            | InstructionType::LdDtVx
+            | InstructionType::LdStVx
+            | InstructionType::AddIVx
+            | InstructionType::LdFVx
+            | InstructionType::LdHfVx
+            | InstructionType::LdVxI
+            | InstructionType::LoadVxVy
+            | InstructionType::AddNibVxVy
+            | InstructionType::LdRVx
+            | InstructionType::LdVxR
+    )
+}
+
+impl Cpu {
+    /// Run the compiled block at pc, stopping early after `max_instructions`
+    /// instructions or once `max_cycles` machine cycles have passed
+    ///
+    /// Leaves the CPU in the same state as stepping the interpreter over the
+    /// same instructions. Falls back to [`Cpu::step`] where no block can be
+    /// compiled, such as with the VIP memory layout or after the code under pc
+    /// was overwritten.
+    pub fn run_block(
+        &mut self,
+        max_instructions: u32,
+        max_cycles: u32,
+    ) -> Result<BlockRun, Chip8Error> {
+        let mut run = BlockRun::default();
+        if !self.is_running() {
+            return Ok(run);
+        }
+
+        let Some(block) = self.block_at(self.pc) else {
+            let cycles = self.step()?;
+            run.instructions = (cycles > 0) as u32;
+            run.cycles = cycles;
+            return Ok(run);
+        };
+
+        for &(handler, instr, cycles) in &block.ops {
+            if run.instructions >= max_instructions || run.cycles >= max_cycles {
+                return Ok(run);
+            }
+            handler(self, instr)?;
+            run.instructions += 1;
+            run.cycles += cycles;
+        }
+
+        if let Some((instr, instr_type)) = block.exit {
+            if run.instructions >= max_instructions || run.cycles >= max_cycles {
+                return Ok(run);
+            }
+            let cycles = self.vip_cycles(&instr_type, &instr);
+            self.execute(instr_type, instr)?;
+            run.instructions += 1;
+            run.cycles += cycles;
+        }
+
+        Ok(run)
+    }
+
+    /// Cached block at the address, compiled on first use
+    fn block_at(&mut self, address: u16) -> Option<Arc<Block>> {
+        // Blocks skip the per-instruction sync of state mirrored in VIP memory
+        if self.memory_layout != MemoryLayout::Separate {
+            return None;
+        }
+
+        if let Some(Some(block)) = self.block_cache.blocks.get(address as usize) {
+            return Some(block.clone());
+        }
+
+        let block = Arc::new(self.compile_block(address)?);
+        let cache = &mut self.block_cache;
+        if cache.blocks.is_empty() {
+            cache.blocks = vec![None; self.decode_cache.len()];
+        }
+        cache.blocks[address as usize] = Some(block.clone());
+        cache.starts.push(address);
+        Some(block)
+    }
+
+    /// Translate the instructions from the address up to the next one that
+    /// does more than advance pc
+    ///
+    /// Returns `None` when the first instruction cannot be decoded, leaving
+    /// the interpreter to report the error.
+    fn compile_block(&self, start: u16) -> Option<Block> {
+        let mut ops = Vec::new();
+        let mut address = start as usize;
+        // pc only reaches the first 64K, and VIP mirrored state is never compiled
+        let end = self.decode_cache.len();
+
+        while address < end {
+            let Some((instr, instr_type)) = self
+                .fetch_at(address as u16)
+                .ok()
+                .and_then(|instr| Some((instr, self.decode(&instr)?)))
+            else {
+                break;
+            };
+
+            if !is_straight(instr_type) {
+                return Some(Block {
+                    end: address + 2,
+                    ops,
+                    exit: Some((instr, instr_type)),
+                });
+            }
+
+            let cycles = self.vip_cycles(&instr_type, &instr);
+            ops.push((Cpu::handler(instr_type), instr, cycles));
+            address += 2;
+        }
+
+        (!ops.is_empty()).then_some(Block {
+            end: address,
+            ops,
+            exit: None,
+        })
+    }
+}
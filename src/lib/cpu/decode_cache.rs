@@ -0,0 +1,71 @@
+use super::instructions::{Instruction, InstructionType};
+
+/// Decoded instructions keyed by the address they were fetched from
+///
+/// Entries are dropped when the memory under them is written, so programs
+/// that modify their own code still run what is in memory.
+#[derive(Debug)]
+pub struct DecodeCache {
+    /// Addresses that can be cached
+    size: usize,
+    /// Allocated by the first insert, so CPUs stay cheap to create and clone
+    entries: Vec<Option<(Instruction, InstructionType)>>,
+}
+
+impl DecodeCache {
+    /// Empty cache for instructions starting below `size`
+    pub fn new(size: usize) -> DecodeCache {
+        DecodeCache {
+            size,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Number of addresses that can be cached
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, address: u16) -> Option<(Instruction, InstructionType)> {
+        self.entries.get(address as usize).copied().flatten()
+    }
+
+    /// Remember the decoded instruction, ignored past the end of the cache
+    pub fn insert(&mut self, address: u16, instr: Instruction, instr_type: InstructionType) {
+        if address as usize >= self.size {
+            return;
+        }
+        if self.entries.is_empty() {
+            self.entries = vec![None; self.size];
+        }
+        self.entries[address as usize] = Some((instr, instr_type));
+    }
+
+    /// Drop the instructions overlapping `len` bytes of memory from `start`
+    pub fn invalidate(&mut self, start: usize, len: usize) {
+        // The instruction starting the byte before also covers the first byte
+        let first = start.saturating_sub(1).min(self.entries.len());
+        let end = start.saturating_add(len).min(self.entries.len());
+        self.entries[first..end].fill(None);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+}
+
+/// Clones start empty and decode again on demand, copying the entries would
+/// cost more than refilling the few a program runs
+impl Clone for DecodeCache {
+    fn clone(&self) -> DecodeCache {
+        DecodeCache::new(self.size)
+    }
+}
+
+/// Deliberately always equal: cached decodes are derived from memory, so
+/// they don't make two CPUs differ
+impl PartialEq for DecodeCache {
+    fn eq(&self, _other: &DecodeCache) -> bool {
+        true
+    }
+}
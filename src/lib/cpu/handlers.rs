@@ -1,107 +1,161 @@
-use super::constants::{DISPLAY_HEIGHT, DISPLAY_WIDTH};
+use std::sync::Arc;
+
+use super::constants::{
+    AUDIO_PATTERN_SIZE, HIRES_DISPLAY_HEIGHT, HIRES_DISPLAY_WIDTH, MEGA_DISPLAY_HEIGHT,
+    MEGA_DISPLAY_WIDTH, SAMPLE_HEADER_SIZE, SYS_INSTRUCTION_LIMIT, VIP_STACK_POINTER_OFFSET,
+};
 use super::instructions::Instruction;
-use super::Cpu;
+use super::{Cpu, KeyWait};
+use crate::cdp1802::Cdp1802;
+use crate::display::{BlendMode, ColorDisplay, ALL_PLANES, PLANE_COUNT, ZONE_HEIGHT, ZONE_WIDTH};
+use crate::error::Chip8Error;
+use crate::font::{BIG_FONT_ADDRESS, BIG_FONT_SPRITE_SIZE, FONT_SPRITE_SIZE};
+use crate::quirks::MemoryIncrement;
+use crate::state::Sample;
+
+/// Clear the selected display planes
+pub fn cls(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.display_buffer.clear(cpu.plane_mask);
+    if let Some(color_display) = &mut cpu.color_display {
+        color_display.clear();
+    }
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
 
 /// Return from subroutine
-pub fn ret(cpu: &mut Cpu, _instr: Instruction) {
-    // Pop from stack
-    cpu.pc = cpu.stack[cpu.sp as usize];
-    if cpu.sp > 0 {
-        cpu.sp -= 1;
-    }
-    // Go to next instruction past the call
-    cpu.pc += 2;
+pub fn ret(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    // Pop return address from stack
+    cpu.pc = cpu.stack.pop().ok_or(Chip8Error::StackUnderflow {
+        address: cpu.pc,
+        opcode: instr.raw_instr,
+    })?;
+    Ok(())
 }
 
 /// Jump to address NNN
-pub fn jmp(cpu: &mut Cpu, instr: Instruction) {
+pub fn jmp(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     cpu.pc = instr.nnn;
+    Ok(())
 }
 
 /// Call subroutine at address NNN
-pub fn call_nnn(cpu: &mut Cpu, instr: Instruction) {
-    cpu.sp += 1;
-    cpu.stack[cpu.sp as usize] = cpu.pc;
+pub fn call_nnn(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    // Push address of the instruction after the call
+    if !cpu.stack.push(cpu.pc.wrapping_add(2)) {
+        return Err(Chip8Error::StackOverflow {
+            address: cpu.pc,
+            opcode: instr.raw_instr,
+        });
+    }
+
     cpu.pc = instr.nnn;
+    Ok(())
 }
 
 /// Skip next instruction if Vx == kk
-pub fn se_vx_kk(cpu: &mut Cpu, instr: Instruction) {
+pub fn se_vx_kk(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     if cpu.gp_reg[instr.x as usize] == instr.kk {
-        cpu.pc += 4;
-        return;
+        cpu.skip_next_instruction();
+        return Ok(());
     }
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Skip next instruction if Vx != kk
-pub fn sne_vx_kk(cpu: &mut Cpu, instr: Instruction) {
+pub fn sne_vx_kk(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     if cpu.gp_reg[instr.x as usize] != instr.kk {
-        cpu.pc += 4;
-        return;
+        cpu.skip_next_instruction();
+        return Ok(());
     }
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Skip next instruction if Vx == Vy
-pub fn se_vx_vy(cpu: &mut Cpu, instr: Instruction) {
+pub fn se_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     if cpu.gp_reg[instr.x as usize] == cpu.gp_reg[instr.y as usize] {
-        cpu.pc += 4;
-        return;
+        cpu.skip_next_instruction();
+        return Ok(());
     }
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Load value NN to register VX
-pub fn ld_vx_kk(cpu: &mut Cpu, instr: Instruction) {
+pub fn ld_vx_kk(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     cpu.gp_reg[instr.x as usize] = instr.kk;
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Add value kk to register VX and store result in VX
-pub fn add_vx_kk(cpu: &mut Cpu, instr: Instruction) {
+pub fn add_vx_kk(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     let sum: u16 = (cpu.gp_reg[instr.x as usize] as u16) + (instr.kk as u16);
     cpu.gp_reg[instr.x as usize] = sum as u8;
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Put value of register VY to register VX
-pub fn ld_vx_vy(cpu: &mut Cpu, instr: Instruction) {
+pub fn ld_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     cpu.gp_reg[instr.x as usize] = cpu.gp_reg[instr.y as usize];
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Bitwise OR of VX and VY and store result in VX
-pub fn or_vx_vy(cpu: &mut Cpu, instr: Instruction) {
+pub fn or_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     cpu.gp_reg[instr.x as usize] |= cpu.gp_reg[instr.y as usize];
-    cpu.pc += 2;
+
+    if cpu.quirks.vf_reset {
+        cpu.gp_reg[0xF] = 0;
+    }
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Bitwise AND of VX and VY and store result in VX
-pub fn and_vx_vy(cpu: &mut Cpu, instr: Instruction) {
+pub fn and_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     cpu.gp_reg[instr.x as usize] &= cpu.gp_reg[instr.y as usize];
-    cpu.pc += 2;
+
+    if cpu.quirks.vf_reset {
+        cpu.gp_reg[0xF] = 0;
+    }
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Bitwise XOR of VX and VY and store result in VX
-pub fn xor_vx_vy(cpu: &mut Cpu, instr: Instruction) {
+pub fn xor_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     cpu.gp_reg[instr.x as usize] ^= cpu.gp_reg[instr.y as usize];
-    cpu.pc += 2;
+
+    if cpu.quirks.vf_reset {
+        cpu.gp_reg[0xF] = 0;
+    }
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Add VX and VY and store result in VX. If overflow, set VF to 1
-pub fn add_vx_vy(cpu: &mut Cpu, instr: Instruction) {
+pub fn add_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     let sum: u16 = (cpu.gp_reg[instr.x as usize] as u16) + (cpu.gp_reg[instr.y as usize] as u16);
     cpu.gp_reg[instr.x as usize] = sum as u8;
 
     // If overflowed, set VF to 1, 0 if not
     cpu.gp_reg[0xF] = (sum > 0xFF) as u8;
 
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Subtract VY from VX and store difference in VX, If borrowed, set VF to 0
-pub fn sub_vx_vy(cpu: &mut Cpu, instr: Instruction) {
+pub fn sub_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     let vx: i16 = cpu.gp_reg[instr.x as usize] as i16;
     let vy: i16 = cpu.gp_reg[instr.y as usize] as i16;
     let diff = vx - vy;
@@ -111,12 +165,15 @@ pub fn sub_vx_vy(cpu: &mut Cpu, instr: Instruction) {
     // If borrowed, set VF to 0, 1 if not
     cpu.gp_reg[0xF] = (vx > vy) as u8;
 
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Shift right VX by 1, store least significant bit of VX in VF
-pub fn shr_vx_vy(cpu: &mut Cpu, instr: Instruction) {
-    let vx: u8 = cpu.gp_reg[instr.x as usize];
+///
+/// With the shift quirk, VY is shifted and stored in VX instead
+pub fn shr_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let vx: u8 = shift_source(cpu, &instr);
 
     // Store least significant bit of VX in VF
     cpu.gp_reg[0xF] = vx & 0x01;
@@ -124,11 +181,12 @@ pub fn shr_vx_vy(cpu: &mut Cpu, instr: Instruction) {
     // Shift right VX by 1
     cpu.gp_reg[instr.x as usize] = vx >> 1;
 
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Subtract VY from VX and store the difference in VX. Set VF to 1 if borrow
-pub fn subn_vx_vy(cpu: &mut Cpu, instr: Instruction) {
+pub fn subn_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     let vx: i16 = cpu.gp_reg[instr.x as usize] as i16;
     let vy: i16 = cpu.gp_reg[instr.y as usize] as i16;
     let diff = vy - vx;
@@ -138,12 +196,15 @@ pub fn subn_vx_vy(cpu: &mut Cpu, instr: Instruction) {
     // If borrowed, set VF to 0, 1 if not
     cpu.gp_reg[0xF] = (vx > vy) as u8;
 
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Shift left VX by 1, store most significant bit of VX in VF
-pub fn shl_vx_vy(cpu: &mut Cpu, instr: Instruction) {
-    let vx: u8 = cpu.gp_reg[instr.x as usize];
+///
+/// With the shift quirk, VY is shifted and stored in VX instead
+pub fn shl_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let vx: u8 = shift_source(cpu, &instr);
 
     // Store most significant bit of VX in VF
     cpu.gp_reg[0xF] = (vx >> 7) & 1;
@@ -151,77 +212,223 @@ pub fn shl_vx_vy(cpu: &mut Cpu, instr: Instruction) {
     // Shift left VX by 1
     cpu.gp_reg[instr.x as usize] = vx << 1;
 
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Skip next instruction if Vx != Vy
-pub fn sne_vx_vy(cpu: &mut Cpu, instr: Instruction) {
+pub fn sne_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     if cpu.gp_reg[instr.x as usize] != cpu.gp_reg[instr.y as usize] {
-        cpu.pc += 4;
-        return;
+        cpu.skip_next_instruction();
+        return Ok(());
     }
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Value of register I is set to NNN
-pub fn ld_i_nnn(cpu: &mut Cpu, instr: Instruction) {
-    cpu.i_reg = instr.nnn;
-    cpu.pc += 2;
+pub fn ld_i_nnn(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.i_reg = instr.nnn as u32;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Jump to address NNN plus V0
+///
+/// With the jump quirk, jump to address XNN plus VX instead
+pub fn jmp_v0_nnn(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let offset_reg = if cpu.quirks.jump_vx { instr.x } else { 0x0 };
+    cpu.pc = instr.nnn + (cpu.gp_reg[offset_reg as usize] as u16);
+    Ok(())
+}
+
+/// Set VX to a random byte AND kk
+pub fn rnd_vx_kk(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.gp_reg[instr.x as usize] = cpu.rng.next_u8() & instr.kk;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// DRW VX, VY, N: Display n-byte sprite starting
 /// at memory I at (VX, VY), set VF = collison
-pub fn drw_vx_vy_n(cpu: &mut Cpu, instr: Instruction) {
-    let x = cpu.gp_reg[instr.x as usize] as u16;
-    let y = cpu.gp_reg[instr.y as usize] as u16;
+///
+/// With SUPER-CHIP, DRW VX, VY, 0 displays a 16x16 sprite of 32 bytes instead.
+/// The sprite position wraps around the display. Pixels past the edges wrap
+/// around as well, unless the clipping quirk is enabled.
+///
+/// With XO-CHIP, the sprite is drawn to every selected plane, reading the
+/// data for each plane one after the other starting at I.
+///
+/// In MegaChip mode, the sprite is drawn to the colour display instead.
+pub fn drw_vx_vy_n(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    if cpu.color_display.is_some() {
+        return drw_color(cpu, instr);
+    }
+
+    let (sprite_width, sprite_height) = if instr.n == 0 && cpu.instruction_set.has_super_chip() {
+        (16, 16)
+    } else {
+        (8, instr.n as usize)
+    };
+    let bytes_per_row = sprite_width / 8;
+
+    let width = cpu.display_buffer.width();
+    let height = cpu.display_buffer.height();
+    let x = cpu.gp_reg[instr.x as usize] as usize % width;
+    let y = cpu.gp_reg[instr.y as usize] as usize % height;
+    let sprite_size = sprite_height * bytes_per_row;
+    let plane_count = cpu.plane_mask.count_ones() as usize;
+    cpu.check_memory_range(&instr, cpu.i_reg as usize, sprite_size * plane_count)?;
     cpu.gp_reg[0xf] = 0;
 
-    for row in 0..instr.n {
-        let pixel = cpu.memory[(cpu.i_reg + (row as u16)) as usize];
-        for bit in 0..8 {
-            // Check if the bit in pixel is set
-            if pixel & (0x80 >> bit) > 0 {
-                let x_idx = (x + bit) as usize % DISPLAY_WIDTH;
-                let y_idx = (y + (row as u16)) as usize % DISPLAY_HEIGHT;
-
-                // Set VF to 1 if pixel in display buffer changed from 1 to 0
-                if cpu.display_buffer[x_idx][y_idx] == 1 {
-                    cpu.gp_reg[0xf] = 1;
-                }
-                cpu.display_buffer[x_idx][y_idx] ^= 1;
+    let mut sprite_start = cpu.i_reg as usize;
+    for plane in (0..PLANE_COUNT).map(|plane| 1 << plane) {
+        if cpu.plane_mask & plane == 0 {
+            continue;
+        }
+
+        for row in 0..sprite_height {
+            if cpu.quirks.clip_sprites && y + row >= height {
+                break;
+            }
+
+            let row_start = sprite_start + row * bytes_per_row;
+            let pixels = cpu.memory[row_start..row_start + bytes_per_row]
+                .iter()
+                .fold(0u16, |pixels, &byte| (pixels << 8) | byte as u16);
+
+            // Set VF to 1 if any pixel in display buffer changed from 1 to 0
+            let wrap = !cpu.quirks.clip_sprites;
+            let y_idx = (y + row) % height;
+            if cpu
+                .display_buffer
+                .draw_row(x, y_idx, pixels, sprite_width, plane, wrap)
+            {
+                cpu.gp_reg[0xf] = 1;
             }
         }
+
+        sprite_start += sprite_size;
+    }
+
+    // Halt until the next display refresh
+    if cpu.quirks.display_wait {
+        cpu.vblank_wait = true;
     }
 
     cpu.draw_flag = true;
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Skip next instruction if key with the value of VX is pressed
+pub fn skp_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let key = (cpu.gp_reg[instr.x as usize] & 0xF) as usize;
+    if cpu.keypad[key] == 1 {
+        cpu.skip_next_instruction();
+        return Ok(());
+    }
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Skip next instruction if key with the value of VX is not pressed
+pub fn sknp_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let key = (cpu.gp_reg[instr.x as usize] & 0xF) as usize;
+    if cpu.keypad[key] == 0 {
+        cpu.skip_next_instruction();
+        return Ok(());
+    }
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set VX to the value of the delay timer
+pub fn ld_vx_dt(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.gp_reg[instr.x as usize] = cpu.delay_timer;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Wait for a key press and release, then store the value of the key in VX
+///
+/// Like the COSMAC VIP, the wait completes on release. A key already held
+/// down when the instruction executes counts as the pressed key.
+pub fn ld_vx_k(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let held_key = cpu.keypad.iter().position(|&key| key == 1);
+
+    cpu.key_wait = Some(KeyWait {
+        register: instr.x,
+        key: held_key.map(|key| key as u8),
+    });
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set the delay timer to VX
+pub fn ld_dt_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.delay_timer = cpu.gp_reg[instr.x as usize];
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set the sound timer to VX
+pub fn ld_st_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.sound_timer = cpu.gp_reg[instr.x as usize];
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Add VX to I and store result in I
+pub fn add_i_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.i_reg = cpu.i_reg.wrapping_add(cpu.gp_reg[instr.x as usize] as u32);
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set I to the location of the font sprite for the digit in VX
+pub fn ld_f_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let digit = (cpu.gp_reg[instr.x as usize] & 0xF) as u32;
+    cpu.i_reg = digit * FONT_SPRITE_SIZE;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Store registers V0 to VX (inclusive) to main memory starting at I
-pub fn ld_i_vx(cpu: &mut Cpu, instr: Instruction) {
+pub fn ld_i_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     let num_registers: usize = (instr.x + 1) as usize;
+    cpu.check_memory_range(&instr, cpu.i_reg as usize, num_registers)?;
 
     for register in 0..num_registers {
         let mem_index: usize = (cpu.i_reg as usize) + register;
         cpu.memory[mem_index] = cpu.gp_reg[register];
     }
+    cpu.invalidate_code(cpu.i_reg as usize, num_registers);
 
-    cpu.pc += 2;
+    increment_i(cpu, &instr);
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
 /// Load memory starting from I register to registers V0 to VX
-pub fn ld_vx_i(cpu: &mut Cpu, instr: Instruction) {
+pub fn ld_vx_i(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     let num_registers: usize = (instr.x + 1) as usize;
+    cpu.check_memory_range(&instr, cpu.i_reg as usize, num_registers)?;
 
     for register in 0..num_registers {
         let mem_index: usize = (cpu.i_reg as usize) + register;
         cpu.gp_reg[register] = cpu.memory[mem_index];
     }
 
-    cpu.pc += 2;
+    increment_i(cpu, &instr);
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
 }
 
-pub fn ld_b_vx(cpu: &mut Cpu, instr: Instruction) {
+/// Store BCD representation of VX in memory locations I, I+1 and I+2
+pub fn ld_b_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
     let vx = cpu.gp_reg[instr.x as usize];
 
     // Get hundreds, tens, and ones digit from VX
@@ -230,9 +437,506 @@ pub fn ld_b_vx(cpu: &mut Cpu, instr: Instruction) {
     let tens_digit: u8 = (vx - (hundreds_digit * 100)) / 10;
 
     // Store digits to memory starting at I
-    cpu.memory[cpu.i_reg as usize] = hundreds_digit;
-    cpu.memory[(cpu.i_reg + 1) as usize] = tens_digit;
-    cpu.memory[(cpu.i_reg + 2) as usize] = ones_digit;
+    let i_reg = cpu.i_reg as usize;
+    cpu.check_memory_range(&instr, i_reg, 3)?;
+    cpu.memory[i_reg] = hundreds_digit;
+    cpu.memory[i_reg + 1] = tens_digit;
+    cpu.memory[i_reg + 2] = ones_digit;
+    cpu.invalidate_code(i_reg, 3);
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Scroll display down by N pixels
+pub fn scd_n(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.display_buffer
+        .scroll_down(instr.n as usize, cpu.plane_mask);
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Scroll display right by 4 pixels
+pub fn scr(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.display_buffer.scroll_right(4, cpu.plane_mask);
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Scroll display left by 4 pixels
+pub fn scl(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.display_buffer.scroll_left(4, cpu.plane_mask);
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Exit the interpreter, halting execution on this instruction
+pub fn exit(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.halted = true;
+    Ok(())
+}
+
+/// Switch to the configured low resolution mode, 64x32 by default
+pub fn low(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.display_buffer
+        .set_resolution(cpu.display_width, cpu.display_height);
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Switch to 128x64 high resolution mode
+pub fn high(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.display_buffer
+        .set_resolution(HIRES_DISPLAY_WIDTH, HIRES_DISPLAY_HEIGHT);
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set I to the location of the large font sprite for the digit in VX
+pub fn ld_hf_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let digit = (cpu.gp_reg[instr.x as usize] & 0xF) as u32;
+    cpu.i_reg = BIG_FONT_ADDRESS + digit * BIG_FONT_SPRITE_SIZE;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Store registers V0 to VX (inclusive) in the RPL user flags
+pub fn ld_r_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let num_registers: usize = (instr.x + 1) as usize;
+    cpu.rpl_flags[..num_registers].copy_from_slice(&cpu.gp_reg[..num_registers]);
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Load registers V0 to VX (inclusive) from the RPL user flags
+pub fn ld_vx_r(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let num_registers: usize = (instr.x + 1) as usize;
+    cpu.gp_reg[..num_registers].copy_from_slice(&cpu.rpl_flags[..num_registers]);
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Scroll display up by N pixels
+pub fn scu_n(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.display_buffer
+        .scroll_up(instr.n as usize, cpu.plane_mask);
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Store registers VX to VY (inclusive, in either order) to memory starting at I
+///
+/// With CHIP-8E, I is incremented past the stored registers.
+pub fn save_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let registers = register_range(&instr);
+    let count = registers.len();
+    cpu.check_memory_range(&instr, cpu.i_reg as usize, count)?;
+
+    for (offset, register) in registers.into_iter().enumerate() {
+        cpu.memory[cpu.i_reg as usize + offset] = cpu.gp_reg[register];
+    }
+    cpu.invalidate_code(cpu.i_reg as usize, count);
+
+    if cpu.instruction_set.has_chip8e() {
+        cpu.i_reg = cpu.i_reg.wrapping_add(count as u32);
+    }
 
-    cpu.pc += 2;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Load registers VX to VY (inclusive, in either order) from memory starting at I
+///
+/// With CHIP-8E, I is incremented past the loaded registers.
+pub fn load_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let registers = register_range(&instr);
+    let count = registers.len();
+    cpu.check_memory_range(&instr, cpu.i_reg as usize, count)?;
+
+    for (offset, register) in registers.into_iter().enumerate() {
+        cpu.gp_reg[register] = cpu.memory[cpu.i_reg as usize + offset];
+    }
+
+    if cpu.instruction_set.has_chip8e() {
+        cpu.i_reg = cpu.i_reg.wrapping_add(count as u32);
+    }
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set I to the 16-bit address NNNN following the instruction
+pub fn ld_i_long(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let operand = cpu.pc as usize + 2;
+    cpu.check_memory_range(&instr, operand, 2)?;
+    cpu.i_reg = ((cpu.memory[operand] as u32) << 8) | cpu.memory[operand + 1] as u32;
+    cpu.pc = cpu.pc.wrapping_add(4);
+    Ok(())
+}
+
+/// Select the display planes N used by drawing, clearing and scrolling
+pub fn plane_n(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.plane_mask = instr.x & ALL_PLANES;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Load the 16-byte audio pattern buffer from memory starting at I
+pub fn audio_i(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let start = cpu.i_reg as usize;
+    cpu.check_memory_range(&instr, start, AUDIO_PATTERN_SIZE)?;
+
+    let mut pattern = [0; AUDIO_PATTERN_SIZE];
+    pattern.copy_from_slice(&cpu.memory[start..start + AUDIO_PATTERN_SIZE]);
+    cpu.audio_pattern = Some(pattern);
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set the audio pattern playback pitch to VX
+pub fn pitch_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.pitch = cpu.gp_reg[instr.x as usize];
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Leave MegaChip mode, returning to the monochrome display
+pub fn mega_off(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.color_display = None;
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Enter MegaChip mode, drawing to a 256x192 colour display
+pub fn mega_on(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.color_display = Some(ColorDisplay::new(MEGA_DISPLAY_WIDTH, MEGA_DISPLAY_HEIGHT));
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set I to the 24-bit address NN NNNN, the low 16 bits following the instruction
+pub fn ld_i_long_24(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let operand = cpu.pc as usize + 2;
+    cpu.check_memory_range(&instr, operand, 2)?;
+    cpu.i_reg = ((instr.kk as u32) << 16)
+        | ((cpu.memory[operand] as u32) << 8)
+        | cpu.memory[operand + 1] as u32;
+    cpu.pc = cpu.pc.wrapping_add(4);
+    Ok(())
+}
+
+/// Load NN ARGB colours from memory starting at I into palette entries 1 to NN
+pub fn ld_pal(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let start = cpu.i_reg as usize;
+    let count = instr.kk as usize;
+    cpu.check_memory_range(&instr, start, count * 4)?;
+
+    for (entry, argb) in cpu.memory[start..start + count * 4].chunks(4).enumerate() {
+        cpu.palette[entry + 1] = [argb[1], argb[2], argb[3], argb[0]];
+    }
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set the width of MegaChip sprites to NN, 0 meaning 256
+pub fn spr_w(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.sprite_width = sprite_size(instr.kk);
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set the height of MegaChip sprites to NN, 0 meaning 256
+pub fn spr_h(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.sprite_height = sprite_size(instr.kk);
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Play the digitised sound at I, looping if N is 0 and once if N is 1
+///
+/// The sound starts with a header of the 16-bit sample rate and the 24-bit
+/// sample count followed by a zero byte.
+pub fn play_sample(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let start = cpu.i_reg as usize;
+    cpu.check_memory_range(&instr, start, SAMPLE_HEADER_SIZE)?;
+
+    let header = &cpu.memory[start..start + SAMPLE_HEADER_SIZE];
+    let rate = ((header[0] as u16) << 8) | header[1] as u16;
+    let length = ((header[2] as usize) << 16) | ((header[3] as usize) << 8) | header[4] as usize;
+
+    let data_start = start + SAMPLE_HEADER_SIZE;
+    cpu.check_memory_range(&instr, data_start, length)?;
+    cpu.sample = Some(Sample {
+        rate,
+        data: Arc::from(&cpu.memory[data_start..data_start + length]),
+        looping: instr.n == 0,
+    });
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Stop the digitised sound
+pub fn stop_sample(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.sample = None;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Select the blend mode N used to draw MegaChip sprites
+pub fn blend(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.blend_mode = BlendMode::from_index(instr.n).unwrap_or_default();
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set the palette index NN that sets VF when drawn over
+pub fn collision_color(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.collision_color = instr.kk;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Switch to the next CHIP-8X background colour
+pub fn cycle_bg(cpu: &mut Cpu, _instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.color_zones.cycle_background();
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Add VY to VX, adding each nibble separately modulo 8
+pub fn add_nib_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let vx = cpu.gp_reg[instr.x as usize];
+    let vy = cpu.gp_reg[instr.y as usize];
+
+    let low = (vx & 0x07).wrapping_add(vy & 0x07) & 0x07;
+    let high = ((vx >> 4) & 0x07).wrapping_add((vy >> 4) & 0x07) & 0x07;
+    cpu.gp_reg[instr.x as usize] = (high << 4) | low;
+
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set the colour of a block of 8x4 pixel zones to VY
+///
+/// The low and high nibbles of VX give the first and last zone column, those
+/// of VX+1 the first and last zone row.
+pub fn col_zone(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let columns = cpu.gp_reg[instr.x as usize];
+    let rows = cpu.gp_reg[(instr.x as usize + 1) & 0xF];
+    let color = cpu.gp_reg[instr.y as usize] & 0x7;
+
+    for column in (columns & 0xF)..=(columns >> 4) {
+        for zone_row in (rows & 0xF)..=(rows >> 4) {
+            let top = zone_row as usize * ZONE_HEIGHT;
+            for row in top..top + ZONE_HEIGHT {
+                cpu.color_zones.set_color(column as usize, row, color);
+            }
+        }
+    }
+
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Set the colour of the 8xN pixel area at (VX, VX+1) to VY
+pub fn col_area(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let x = cpu.gp_reg[instr.x as usize] as usize;
+    let y = cpu.gp_reg[(instr.x as usize + 1) & 0xF] as usize;
+    let color = cpu.gp_reg[instr.y as usize] & 0x7;
+
+    for column in x / ZONE_WIDTH..=x.div_ceil(ZONE_WIDTH) {
+        for row in y..y + instr.n as usize {
+            cpu.color_zones.set_color(column, row, color);
+        }
+    }
+
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Skip next instruction if key with the value of VX is pressed on the second keypad
+pub fn skp2_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let key = (cpu.gp_reg[instr.x as usize] & 0xF) as usize;
+    if cpu.keypad2[key] == 1 {
+        cpu.skip_next_instruction();
+        return Ok(());
+    }
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Skip next instruction if key with the value of VX is not pressed on the second keypad
+pub fn sknp2_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let key = (cpu.gp_reg[instr.x as usize] & 0xF) as usize;
+    if cpu.keypad2[key] == 0 {
+        cpu.skip_next_instruction();
+        return Ok(());
+    }
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Write VX to the output port
+pub fn out_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.io_output = cpu.gp_reg[instr.x as usize];
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Read the input port latch into VX
+pub fn in_vx(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.gp_reg[instr.x as usize] = cpu.io_input;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Skip next instruction if VX > VY
+pub fn sgt_vx_vy(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    if cpu.gp_reg[instr.x as usize] > cpu.gp_reg[instr.y as usize] {
+        cpu.skip_next_instruction();
+        return Ok(());
+    }
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// Branch back NN bytes from the next instruction
+pub fn branch_back(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.pc = cpu.pc.wrapping_add(2).wrapping_sub(instr.kk as u16);
+    Ok(())
+}
+
+/// Branch forward NN bytes from the next instruction
+pub fn branch_fwd(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    cpu.pc = cpu.pc.wrapping_add(2).wrapping_add(instr.kk as u16);
+    Ok(())
+}
+
+/// Call the CDP1802 machine code subroutine at NNN, which returns with SEP R4
+///
+/// Registers are set up like the VIP interpreter does: R2 is the stack
+/// pointer, R3 the program counter, R5 the CHIP-8 program counter, R6 and R7
+/// point at VX and VY and RA holds I. Changes to R5 and RA are kept.
+pub fn sys_nnn(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    // Only decoded with the VIP memory layout
+    let registers = cpu.register_address().unwrap_or_default();
+
+    let mut core = Cdp1802::new();
+    core.r[0x2] = (cpu.memory.len() - VIP_STACK_POINTER_OFFSET) as u16;
+    core.r[0x3] = instr.nnn;
+    core.r[0x5] = cpu.pc.wrapping_add(2);
+    core.r[0x6] = (registers + instr.x as usize) as u16;
+    core.r[0x7] = (registers + instr.y as usize) as u16;
+    core.r[0xA] = cpu.i_reg as u16;
+    core.p = 0x3;
+    core.x = 0x2;
+
+    // Machine code may write anywhere in memory
+    cpu.invalidate_decode_cache();
+
+    for _ in 0..SYS_INSTRUCTION_LIMIT {
+        core.step(cpu.memory.as_mut_slice());
+
+        if core.p == 0x4 {
+            cpu.pc = core.r[0x5];
+            cpu.i_reg = core.r[0xA] as u32;
+            return Ok(());
+        }
+    }
+
+    Err(Chip8Error::MachineCodeLimit {
+        address: cpu.pc,
+        opcode: instr.raw_instr,
+    })
+}
+
+/// Draw a MegaChip sprite of palette indices at (VX, VY), set VF = collision
+///
+/// Index 0 is transparent and pixels past the edges are clipped. VF is set
+/// when a pixel is drawn over one of the collision colour.
+fn drw_color(cpu: &mut Cpu, instr: Instruction) -> Result<(), Chip8Error> {
+    let (width, height) = (cpu.sprite_width, cpu.sprite_height);
+    let sprite_start = cpu.i_reg as usize;
+    cpu.check_memory_range(&instr, sprite_start, width * height)?;
+
+    let x = cpu.gp_reg[instr.x as usize] as usize;
+    let y = cpu.gp_reg[instr.y as usize] as usize;
+    let mut collision = false;
+
+    if let Some(display) = &mut cpu.color_display {
+        for row in 0..height.min(display.height().saturating_sub(y)) {
+            for col in 0..width.min(display.width().saturating_sub(x)) {
+                let index = cpu.memory[sprite_start + row * width + col];
+                if index == 0 {
+                    continue;
+                }
+
+                let color = cpu.palette[index as usize];
+                let previous = display.draw(x + col, y + row, index, color, cpu.blend_mode);
+                if previous != 0 && previous == cpu.collision_color {
+                    collision = true;
+                }
+            }
+        }
+    }
+
+    cpu.gp_reg[0xf] = collision as u8;
+    cpu.draw_flag = true;
+    cpu.pc = cpu.pc.wrapping_add(2);
+    Ok(())
+}
+
+/// MegaChip sprite dimension set by 03NN and 04NN
+fn sprite_size(size: u8) -> usize {
+    if size == 0 {
+        256
+    } else {
+        size as usize
+    }
+}
+
+/// Register shifted by 8XY6 and 8XYE
+fn shift_source(cpu: &Cpu, instr: &Instruction) -> u8 {
+    if cpu.quirks.shift_vy {
+        cpu.gp_reg[instr.y as usize]
+    } else {
+        cpu.gp_reg[instr.x as usize]
+    }
+}
+
+/// Increment I after FX55 and FX65 according to the memory quirk
+fn increment_i(cpu: &mut Cpu, instr: &Instruction) {
+    let increment = match cpu.quirks.memory_increment {
+        MemoryIncrement::None => 0,
+        MemoryIncrement::X => instr.x as u32,
+        MemoryIncrement::XPlusOne => instr.x as u32 + 1,
+    };
+    cpu.i_reg = cpu.i_reg.wrapping_add(increment);
+}
+
+/// Registers named by 5XY2 and 5XY3, counting down from VX when X > Y
+fn register_range(instr: &Instruction) -> impl ExactSizeIterator<Item = usize> {
+    let (x, y) = (instr.x as usize, instr.y as usize);
+    let (low, high) = (x.min(y), x.max(y));
+    (low..high + 1).map(move |register| {
+        if x <= y {
+            register
+        } else {
+            low + high - register
+        }
+    })
 }
@@ -1,4 +1,5 @@
 /// Breakdown of CHIP-8 Instruction
+#[derive(Debug, Clone, Copy)]
 pub struct Instruction {
     /// Raw unparsed instruction
     pub raw_instr: u16,
@@ -17,7 +18,7 @@ pub struct Instruction {
 }
 
 /// CHIP-8 Instruction Set
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum InstructionType {
     Cls,
     Ret,
@@ -53,4 +54,101 @@ pub enum InstructionType {
     LdBVx,
     LdIVx,
     LdVxI,
+    // SUPER-CHIP
+    ScdN,
+    Scr,
+    Scl,
+    Exit,
+    Low,
+    High,
+    LdHfVx,
+    LdRVx,
+    LdVxR,
+    // XO-CHIP
+    ScuN,
+    SaveVxVy,
+    LoadVxVy,
+    LdILong,
+    PlaneN,
+    AudioI,
+    PitchVx,
+    // MegaChip
+    MegaOff,
+    MegaOn,
+    LdILong24,
+    LdPal,
+    SprW,
+    SprH,
+    PlaySample,
+    StopSample,
+    Blend,
+    CollisionColor,
+    // CHIP-8X
+    CycleBg,
+    AddNibVxVy,
+    ColZone,
+    ColArea,
+    Skp2Vx,
+    SkNp2Vx,
+    OutVx,
+    InVx,
+    // CHIP-8E
+    SgtVxVy,
+    BranchBack,
+    BranchFwd,
+    SysNnn,
+}
+
+/// Instruction set decoded by the CPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionSet {
+    /// Original CHIP-8 instructions
+    Chip8,
+    /// CHIP-8 with the SUPER-CHIP 1.1 extensions
+    SuperChip,
+    /// SUPER-CHIP with the XO-CHIP extensions
+    XoChip,
+    /// SUPER-CHIP with the MegaChip8 extensions
+    MegaChip,
+    /// CHIP-8 with the CHIP-8X extensions for the VP-590 and VP-595 boards
+    Chip8X,
+    /// CHIP-8 on the 64x64 two-page display of hi-res CHIP-8
+    HiRes,
+    /// CHIP-8 with the CHIP-8E extensions
+    Chip8E,
+}
+
+impl InstructionSet {
+    /// Whether SUPER-CHIP instructions are decoded
+    pub fn has_super_chip(&self) -> bool {
+        matches!(
+            self,
+            InstructionSet::SuperChip | InstructionSet::XoChip | InstructionSet::MegaChip
+        )
+    }
+
+    /// Whether XO-CHIP instructions are decoded
+    pub fn has_xo_chip(&self) -> bool {
+        matches!(self, InstructionSet::XoChip)
+    }
+
+    /// Whether CHIP-8X instructions are decoded, replacing BNNN
+    pub fn has_chip8x(&self) -> bool {
+        matches!(self, InstructionSet::Chip8X)
+    }
+
+    /// Whether MegaChip8 instructions are decoded
+    pub fn has_mega_chip(&self) -> bool {
+        matches!(self, InstructionSet::MegaChip)
+    }
+
+    /// Whether hi-res CHIP-8 boots programs into the 64x64 display
+    pub fn has_hires(&self) -> bool {
+        matches!(self, InstructionSet::HiRes)
+    }
+
+    /// Whether CHIP-8E instructions are decoded
+    pub fn has_chip8e(&self) -> bool {
+        matches!(self, InstructionSet::Chip8E)
+    }
 }
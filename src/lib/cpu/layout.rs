@@ -0,0 +1,114 @@
+use super::instructions::InstructionType;
+use super::Cpu;
+use crate::constants::{
+    DISPLAY_HEIGHT, DISPLAY_WIDTH, VIP_DISPLAY_OFFSET, VIP_REGISTER_OFFSET, VIP_STACK_OFFSET,
+};
+use crate::display::row_from_bytes;
+use crate::machine::MemoryLayout;
+
+impl Cpu {
+    /// Where the call stack, registers and framebuffer are kept
+    pub fn memory_layout(&self) -> MemoryLayout {
+        self.memory_layout
+    }
+
+    /// Address of the call stack in RAM with the VIP memory layout
+    ///
+    /// Return addresses are stored big-endian, oldest first.
+    pub fn stack_address(&self) -> Option<usize> {
+        self.layout_address(VIP_STACK_OFFSET)
+    }
+
+    /// Address of V0 in RAM with the VIP memory layout
+    pub fn register_address(&self) -> Option<usize> {
+        self.layout_address(VIP_REGISTER_OFFSET)
+    }
+
+    /// Address of the framebuffer in RAM with the VIP memory layout
+    ///
+    /// Pixels are packed eight to a byte, most significant bit first.
+    pub fn display_address(&self) -> Option<usize> {
+        self.layout_address(VIP_DISPLAY_OFFSET)
+    }
+
+    fn layout_address(&self, offset: usize) -> Option<usize> {
+        match self.memory_layout {
+            MemoryLayout::Separate => None,
+            MemoryLayout::Vip => Some(self.memory.len() - offset),
+        }
+    }
+
+    /// Copy the stack, registers and framebuffer to RAM
+    pub(super) fn store_layout(&mut self) {
+        let (Some(stack), Some(registers), Some(display)) = (
+            self.stack_address(),
+            self.register_address(),
+            self.display_address(),
+        ) else {
+            return;
+        };
+
+        let stack_area = self.memory[stack..registers].chunks_exact_mut(2);
+        for (bytes, frame) in stack_area.zip(self.stack.slots()) {
+            bytes.copy_from_slice(&frame.to_be_bytes());
+        }
+
+        let register_count = self.gp_reg.len();
+        self.memory[registers..registers + register_count].copy_from_slice(&self.gp_reg);
+
+        if self.has_vip_display() {
+            let rows = self.display_buffer.plane(0);
+            let row_bytes = self.memory[display..].chunks_exact_mut(DISPLAY_WIDTH / 8);
+            for (bytes, row) in row_bytes.zip(rows) {
+                bytes.copy_from_slice(&row.to_be_bytes()[..DISPLAY_WIDTH / 8]);
+            }
+        }
+    }
+
+    /// Reload the stack, registers and framebuffer from RAM after the program wrote to it
+    pub(super) fn load_layout(&mut self) {
+        let (Some(stack), Some(registers), Some(display)) = (
+            self.stack_address(),
+            self.register_address(),
+            self.display_address(),
+        ) else {
+            return;
+        };
+
+        let stack_area = self.memory[stack..registers].chunks_exact(2);
+        for (frame, bytes) in self.stack.slots_mut().iter_mut().zip(stack_area) {
+            *frame = u16::from_be_bytes([bytes[0], bytes[1]]);
+        }
+
+        let register_count = self.gp_reg.len();
+        self.gp_reg
+            .copy_from_slice(&self.memory[registers..registers + register_count]);
+
+        if self.has_vip_display() {
+            for y in 0..DISPLAY_HEIGHT {
+                let start = display + y * DISPLAY_WIDTH / 8;
+                let row = row_from_bytes(&self.memory[start..start + DISPLAY_WIDTH / 8]);
+                if self.display_buffer.set_row(0, y, row) {
+                    self.draw_flag = true;
+                }
+            }
+        }
+    }
+
+    /// Whether the display has the 64x32 resolution of the VIP framebuffer
+    fn has_vip_display(&self) -> bool {
+        self.display_buffer.width() == DISPLAY_WIDTH
+            && self.display_buffer.height() == DISPLAY_HEIGHT
+    }
+}
+
+/// Whether the instruction stores to RAM, possibly over the VIP memory layout
+pub(super) fn writes_memory(instr_type: &InstructionType) -> bool {
+    matches!(
+        instr_type,
+        InstructionType::LdIVx
+            | InstructionType::LdBVx
+            | InstructionType::SaveVxVy
+            | InstructionType::SysNnn
+    )
+}
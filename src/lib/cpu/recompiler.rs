@@ -0,0 +1,318 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use super::blocks::is_straight;
+use super::instructions::{Instruction, InstructionType};
+use super::Cpu;
+use crate::font::FONT_SPRITE_SIZE;
+use crate::machine::MemoryLayout;
+
+/// Bytes per line of the code array, as laid out by rustfmt
+const CODE_LINE_BYTES: usize = 16;
+
+/// Basic block translated to the body of a Rust function
+struct TranslatedBlock {
+    start: usize,
+    /// First address after the last instruction decoded
+    end: usize,
+    body: String,
+}
+
+impl Cpu {
+    /// Translate the program reachable from pc to the source of a Rust module
+    ///
+    /// The module's `run` function is a [`NativeProgram`](super::NativeProgram)
+    /// running one basic block per call like [`Cpu::run_block`]. Register and
+    /// timer instructions are compiled to Rust, everything else is handed to
+    /// the interpreter. A block first checks its code is unchanged in memory,
+    /// and overwritten code or pc outside the discovered code, such as after
+    /// a computed jump, falls back to the interpreter.
+    ///
+    /// The quirks and instruction set of this CPU are built into the code.
+    /// With the VIP memory layout every instruction is handed to the
+    /// interpreter, which keeps the state mirrored in memory in sync.
+    pub fn recompile(&self) -> String {
+        let blocks: Vec<TranslatedBlock> = self
+            .discover_blocks()
+            .into_iter()
+            .filter_map(|start| self.translate_block(start))
+            .collect();
+        let native = blocks.iter().any(|block| block.body.contains("native("));
+
+        let mut out = String::new();
+        out.push_str(
+            "// Translated from a CHIP-8 program by chip8-recompile, do not edit\n\
+             //\n\
+             // Run with Engine::Native(run) on a CPU of the platform it was translated\n\
+             // for, whose quirks and instruction set are built in.\n\
+             \n\
+             use chip8::cpu::{BlockRun, Cpu};\n\
+             use chip8::error::Chip8Error;\n",
+        );
+
+        let code_start = blocks.iter().map(|block| block.start).min();
+        let code_end = blocks.iter().map(|block| block.end).max();
+        if let (Some(start), Some(end)) = (code_start, code_end) {
+            let _ = write!(
+                out,
+                "\n/// First address of the translated code\n\
+                 const CODE_START: usize = {:#05X};\n\
+                 \n\
+                 /// Program bytes the blocks were translated from\n\
+                 const CODE: [u8; {}] = [\n",
+                start,
+                end - start,
+            );
+            for line in self.memory[start..end].chunks(CODE_LINE_BYTES) {
+                let bytes: Vec<String> = line.iter().map(|byte| format!("{:#04X}", byte)).collect();
+                let _ = writeln!(out, "    {},", bytes.join(", "));
+            }
+            out.push_str("];\n");
+        }
+
+        out.push_str(
+            "\n/// Run the translated block at pc, or a single instruction with the\n\
+             /// interpreter where there is none\n\
+             pub fn run(cpu: &mut Cpu, max_instructions: u32, max_cycles: u32) -> Result<BlockRun, Chip8Error> {\n    \
+                 let mut run = BlockRun::default();\n    \
+                 if !cpu.is_running() {\n        \
+                     return Ok(run);\n    \
+                 }\n\n",
+        );
+        if blocks.is_empty() {
+            out.push_str(
+                "    let _ = (max_instructions, max_cycles);\n    let translated = false;\n",
+            );
+        } else {
+            out.push_str(
+                "    let limit = BlockRun {\n        \
+                     instructions: max_instructions,\n        \
+                     cycles: max_cycles,\n    \
+                 };\n    \
+                 let translated = match cpu.pc {\n",
+            );
+            for block in &blocks {
+                let _ = writeln!(
+                    out,
+                    "        {:#05X} => block_{:x}(cpu, &mut run, limit)?,",
+                    block.start, block.start
+                );
+            }
+            out.push_str("        _ => false,\n    };\n");
+        }
+        out.push_str(
+            "\n    // Code outside the blocks, or overwritten since, is left to the interpreter\n    \
+                 if !translated {\n        \
+                     step(cpu, &mut run)?;\n    \
+                 }\n\n    \
+                 Ok(run)\n\
+             }\n",
+        );
+
+        if !blocks.is_empty() {
+            out.push_str(
+                "\n/// Whether the run used up the instruction or cycle limit\n\
+                 fn done(run: &BlockRun, limit: BlockRun) -> bool {\n    \
+                     run.instructions >= limit.instructions || run.cycles >= limit.cycles\n\
+                 }\n\
+                 \n\
+                 /// Whether the code between the addresses is still the translated code\n\
+                 fn intact(cpu: &Cpu, start: usize, end: usize) -> bool {\n    \
+                     cpu.memory.get(start..end) == Some(&CODE[start - CODE_START..end - CODE_START])\n\
+                 }\n",
+            );
+        }
+        if native {
+            out.push_str(
+                "\n/// Count an instruction run by translated code\n\
+                 fn native(run: &mut BlockRun, cycles: u32) {\n    \
+                     run.instructions += 1;\n    \
+                     run.cycles += cycles;\n\
+                 }\n",
+            );
+        }
+        out.push_str(
+            "\n/// Run the instruction at pc with the interpreter\n\
+             fn step(cpu: &mut Cpu, run: &mut BlockRun) -> Result<(), Chip8Error> {\n    \
+                 let cycles = cpu.step()?;\n    \
+                 run.instructions += (cycles > 0) as u32;\n    \
+                 run.cycles += cycles;\n    \
+                 Ok(())\n\
+             }\n",
+        );
+
+        for block in &blocks {
+            let _ = write!(
+                out,
+                "\nfn block_{:x}(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {{\n    \
+                     if !intact(cpu, {:#05X}, {:#05X}) {{\n        \
+                         return Ok(false);\n    \
+                     }}\n\
+                 {}\n    \
+                     Ok(true)\n\
+                 }}\n",
+                block.start, block.start, block.end, block.body
+            );
+        }
+
+        out
+    }
+
+    /// Addresses starting basic blocks, found by following every branch
+    /// from pc
+    fn discover_blocks(&self) -> BTreeSet<usize> {
+        let mut leaders = BTreeSet::from([self.pc as usize]);
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![self.pc as usize];
+
+        while let Some(address) = pending.pop() {
+            if !visited.insert(address) {
+                continue;
+            }
+            let Some((instr, instr_type)) = self.decode_at(address) else {
+                continue;
+            };
+
+            let next = address + 2;
+            let successors = match instr_type {
+                InstructionType::Jmp => vec![instr.nnn as usize],
+                InstructionType::CallNnn => vec![instr.nnn as usize, next],
+                // Targets are only known at run time
+                InstructionType::Ret | InstructionType::Exit | InstructionType::JmpV0Nnn => {
+                    vec![]
+                }
+                InstructionType::SeVxKk
+                | InstructionType::SneVxKk
+                | InstructionType::SeVxVy
+                | InstructionType::SneVxVy
+                | InstructionType::SkpVx
+                | InstructionType::SkNpVx
+                | InstructionType::Skp2Vx
+                | InstructionType::SkNp2Vx
+                | InstructionType::SgtVxVy => {
+                    vec![next, next + self.instruction_size(next as u16) as usize]
+                }
+                InstructionType::BranchBack => vec![next.wrapping_sub(instr.kk as usize)],
+                InstructionType::BranchFwd => vec![next + instr.kk as usize],
+                _ => vec![address + self.instruction_size(address as u16) as usize],
+            };
+
+            if !is_straight(instr_type) {
+                leaders.extend(&successors);
+            }
+            pending.extend(successors);
+        }
+
+        leaders
+    }
+
+    /// Translate the straight-line instructions from the address and the
+    /// instruction ending them, or `None` if nothing there can be decoded
+    fn translate_block(&self, start: usize) -> Option<TranslatedBlock> {
+        let mut body = String::new();
+        let mut address = start;
+
+        while let Some((instr, instr_type)) = self.decode_at(address) {
+            let _ = write!(
+                body,
+                "\n    // {:#05X}: {:04X}\n    \
+                 if done(run, limit) {{\n        \
+                     return Ok(true);\n    \
+                 }}\n",
+                address, instr.raw_instr
+            );
+
+            let straight = is_straight(instr_type);
+            match self.native_code(instr_type, &instr).filter(|_| straight) {
+                Some(code) => {
+                    let cycles = self.vip_cycles(&instr_type, &instr);
+                    for line in code {
+                        let _ = writeln!(body, "    {}", line);
+                    }
+                    let next = (address + 2) as u16;
+                    let _ = writeln!(body, "    cpu.pc = {:#05X};", next);
+                    let _ = writeln!(body, "    native(run, {});", cycles);
+                }
+                None => body.push_str("    step(cpu, run)?;\n"),
+            }
+
+            address += 2;
+            if !straight {
+                break;
+            }
+        }
+
+        (address > start).then_some(TranslatedBlock {
+            start,
+            end: address,
+            body,
+        })
+    }
+
+    /// Decode the instruction at an address pc can reach
+    fn decode_at(&self, address: usize) -> Option<(Instruction, InstructionType)> {
+        if address >= self.decode_cache.len() {
+            return None;
+        }
+        let instr = self.fetch_at(address as u16).ok()?;
+        Some((instr, self.decode(&instr)?))
+    }
+
+    /// Rust statements doing what the handler does apart from advancing pc,
+    /// for instructions simple enough to compile
+    fn native_code(&self, instr_type: InstructionType, instr: &Instruction) -> Option<Vec<String>> {
+        // Native code skips the per-instruction sync of state mirrored in VIP memory
+        if self.memory_layout != MemoryLayout::Separate {
+            return None;
+        }
+
+        let vx = format!("cpu.gp_reg[{:#X}]", instr.x);
+        let vy = format!("cpu.gp_reg[{:#X}]", instr.y);
+
+        let logic = |op: &str| {
+            let mut code = vec![format!("{} {}= {};", vx, op, vy)];
+            if self.quirks.vf_reset {
+                code.push(String::from("cpu.gp_reg[0xF] = 0;"));
+            }
+            code
+        };
+
+        let code = match instr_type {
+            InstructionType::LdVxKk => vec![format!("{} = {:#04X};", vx, instr.kk)],
+            InstructionType::AddVxKk => {
+                vec![format!("{} = {}.wrapping_add({:#04X});", vx, vx, instr.kk)]
+            }
+            InstructionType::LdVxVy => vec![format!("{} = {};", vx, vy)],
+            InstructionType::OrVxVy => logic("|"),
+            InstructionType::AndVxVy => logic("&"),
+            InstructionType::XorVxVy => logic("^"),
+            InstructionType::AddVxVy => vec![
+                format!("let (sum, carry) = {}.overflowing_add({});", vx, vy),
+                format!("{} = sum;", vx),
+                String::from("cpu.gp_reg[0xF] = carry as u8;"),
+            ],
+            InstructionType::SubVxVy => vec![
+                format!("let (vx, vy) = ({}, {});", vx, vy),
+                format!("{} = vx.wrapping_sub(vy);", vx),
+                String::from("cpu.gp_reg[0xF] = (vx > vy) as u8;"),
+            ],
+            InstructionType::LdINnn => vec![format!("cpu.i_reg = {:#05X};", instr.nnn)],
+            InstructionType::LdVxDt => vec![format!("{} = cpu.delay_timer;", vx)],
+            InstructionType::LdDtVx => vec![format!("cpu.delay_timer = {};", vx)],
+            InstructionType::LdStVx => vec![format!("cpu.sound_timer = {};", vx)],
+            InstructionType::AddIVx => {
+                vec![format!(
+                    "cpu.i_reg = cpu.i_reg.wrapping_add({} as u32);",
+                    vx
+                )]
+            }
+            InstructionType::LdFVx => vec![format!(
+                "cpu.i_reg = ({} & 0xF) as u32 * {};",
+                vx, FONT_SPRITE_SIZE
+            )],
+            _ => return None,
+        };
+
+        Some(code)
+    }
+}
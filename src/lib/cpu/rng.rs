@@ -0,0 +1,24 @@
+/// Xorshift pseudo-random number generator used by the RND instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    /// Create generator from seed, a zero seed is replaced as xorshift cannot leave zero
+    pub fn new(seed: u32) -> Rng {
+        Rng {
+            state: if seed == 0 { 0x2545_F491 } else { seed },
+        }
+    }
+
+    /// Generate next random byte
+    pub fn next_u8(&mut self) -> u8 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+
+        // Upper bits of xorshift have the best distribution
+        (self.state >> 24) as u8
+    }
+}
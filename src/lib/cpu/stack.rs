@@ -0,0 +1,74 @@
+/// Behaviour of the call stack when it overflows or underflows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackPolicy {
+    /// Reject the call or return with a stack overflow or underflow error
+    Error,
+    /// Wrap the stack pointer around the stack depth, overwriting the oldest
+    /// frames like interpreters with a fixed width stack pointer
+    Wrap,
+}
+
+/// Subroutine call stack of return addresses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stack {
+    frames: Vec<u16>,
+    /// Number of frames in use, also the index of the next free frame
+    sp: usize,
+    policy: StackPolicy,
+}
+
+impl Stack {
+    pub fn new(depth: usize, policy: StackPolicy) -> Stack {
+        Stack {
+            frames: vec![0; depth],
+            sp: 0,
+            policy,
+        }
+    }
+
+    /// Push return address, returns false if the stack is full
+    pub fn push(&mut self, address: u16) -> bool {
+        if self.sp == self.frames.len() {
+            if self.policy == StackPolicy::Error || self.frames.is_empty() {
+                return false;
+            }
+            self.sp = 0;
+        }
+
+        self.frames[self.sp] = address;
+        self.sp += 1;
+        true
+    }
+
+    /// Pop return address, returns None if the stack is empty
+    pub fn pop(&mut self) -> Option<u16> {
+        if self.sp == 0 {
+            if self.policy == StackPolicy::Error || self.frames.is_empty() {
+                return None;
+            }
+            self.sp = self.frames.len();
+        }
+
+        self.sp -= 1;
+        Some(self.frames[self.sp])
+    }
+
+    /// Return addresses currently on the stack, oldest first
+    pub fn frames(&self) -> &[u16] {
+        &self.frames[..self.sp]
+    }
+
+    /// Every frame up to the stack depth, including stale ones above the stack pointer
+    pub fn slots(&self) -> &[u16] {
+        &self.frames
+    }
+
+    pub fn slots_mut(&mut self) -> &mut [u16] {
+        &mut self.frames
+    }
+
+    /// Maximum number of nested calls
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+}
@@ -0,0 +1,79 @@
+use super::instructions::{Instruction, InstructionType};
+use super::Cpu;
+
+/// Machine cycles the VIP interpreter spends fetching and decoding an instruction
+const FETCH_CYCLES: u32 = 40;
+
+/// Extra machine cycles of a skip instruction when the skip is taken
+const SKIP_CYCLES: u32 = 4;
+
+/// Machine cycles of a DXYN with no rows, and of each row drawn
+const DRW_CYCLES: u32 = 26;
+const DRW_ROW_CYCLES: u32 = 46;
+/// Machine cycles per bit the sprite row is shifted to reach X
+const DRW_SHIFT_CYCLES: u32 = 4;
+
+impl Cpu {
+    /// Machine cycles the COSMAC VIP interpreter takes to run the instruction
+    ///
+    /// Costs depend on operands and are evaluated before the instruction
+    /// runs. Instructions the VIP interpreter lacks are charged like a
+    /// register load.
+    pub(super) fn vip_cycles(&self, instr_type: &InstructionType, instr: &Instruction) -> u32 {
+        let vx = self.gp_reg[instr.x as usize];
+        let vy = self.gp_reg[instr.y as usize];
+        let key_pressed = self.keypad[(vx & 0xF) as usize] == 1;
+
+        let execute = match instr_type {
+            InstructionType::Cls => 3078,
+            InstructionType::Ret => 10,
+            InstructionType::Jmp => 12,
+            InstructionType::CallNnn => 26,
+            InstructionType::SeVxKk => 10 + skip_cycles(vx == instr.kk),
+            InstructionType::SneVxKk => 10 + skip_cycles(vx != instr.kk),
+            InstructionType::SeVxVy => 14 + skip_cycles(vx == vy),
+            InstructionType::SneVxVy => 14 + skip_cycles(vx != vy),
+            InstructionType::LdVxKk => 6,
+            InstructionType::AddVxKk => 10,
+            InstructionType::LdVxVy => 12,
+            InstructionType::OrVxVy
+            | InstructionType::AndVxVy
+            | InstructionType::XorVxVy
+            | InstructionType::AddVxVy
+            | InstructionType::SubVxVy
+            | InstructionType::ShrVxVy
+            | InstructionType::SubnVxVy
+            | InstructionType::ShlVxVy => 44,
+            InstructionType::LdINnn => 12,
+            InstructionType::JmpV0Nnn => 22,
+            InstructionType::RndVxKk => 36,
+            InstructionType::DrwVxVyN => {
+                let shift = vx as u32 % 8;
+                DRW_CYCLES + instr.n as u32 * (DRW_ROW_CYCLES + shift * DRW_SHIFT_CYCLES)
+            }
+            InstructionType::SkpVx => 14 + skip_cycles(key_pressed),
+            InstructionType::SkNpVx => 14 + skip_cycles(!key_pressed),
+            InstructionType::LdVxDt | InstructionType::LdDtVx | InstructionType::LdStVx => 10,
+            InstructionType::LdVxK => 19,
+            InstructionType::AddIVx | InstructionType::LdFVx => 16,
+            // Digits are found by repeated subtraction
+            InstructionType::LdBVx => {
+                let digits = (vx / 100 + vx / 10 % 10 + vx % 10) as u32;
+                80 + 16 * digits
+            }
+            InstructionType::LdIVx | InstructionType::LdVxI => 14 + 14 * (instr.x as u32 + 1),
+            InstructionType::SysNnn => 26,
+            _ => 6,
+        };
+
+        FETCH_CYCLES + execute
+    }
+}
+
+fn skip_cycles(taken: bool) -> u32 {
+    if taken {
+        SKIP_CYCLES
+    } else {
+        0
+    }
+}
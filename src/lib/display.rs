@@ -0,0 +1,381 @@
+use crate::error::Chip8Error;
+
+/// Number of XO-CHIP display planes
+pub const PLANE_COUNT: usize = 2;
+
+/// Mask selecting every display plane
+pub const ALL_PLANES: u8 = (1 << PLANE_COUNT) - 1;
+
+/// Display row of one plane, the leftmost pixel in the most significant bit
+pub type Row = u128;
+
+/// Widest display that fits in a [`Row`]
+pub const MAX_DISPLAY_WIDTH: usize = Row::BITS as usize;
+
+/// Row from pixels packed eight to a byte, leftmost pixel in the most
+/// significant bit, as in the VIP framebuffer
+pub fn row_from_bytes(bytes: &[u8]) -> Row {
+    let mut word = [0; MAX_DISPLAY_WIDTH / 8];
+    let len = bytes.len().min(word.len());
+    word[..len].copy_from_slice(&bytes[..len]);
+    Row::from_be_bytes(word)
+}
+
+/// Framebuffer of up to two bitplanes with a switchable resolution
+///
+/// Each plane is stored as one [`Row`] per line, so a sprite row is drawn
+/// with a shift and XOR and collides when an AND of the rows is non-zero.
+/// Pixels read back as plane bitmasks, bit 0 for the first plane and bit 1
+/// for the second. Plain CHIP-8 and SUPER-CHIP only ever draw to the first
+/// plane, so their pixels are either 0 or 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Display {
+    width: usize,
+    height: usize,
+    /// Rows of each plane from top to bottom, bits past the width are unset
+    planes: [Vec<Row>; PLANE_COUNT],
+}
+
+impl Display {
+    /// Blank display of the given size
+    ///
+    /// # Panics
+    ///
+    /// Panics if either side is 0 or the width is over [`MAX_DISPLAY_WIDTH`], use
+    /// [`Display::try_new`] to handle the error
+    pub fn new(width: usize, height: usize) -> Display {
+        Display::try_new(width, height).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Blank display of the given size, fails if either side is 0 or the
+    /// width is over [`MAX_DISPLAY_WIDTH`]
+    pub fn try_new(width: usize, height: usize) -> Result<Display, Chip8Error> {
+        if width == 0 || height == 0 || width > MAX_DISPLAY_WIDTH {
+            return Err(Chip8Error::InvalidResolution { width, height });
+        }
+
+        Ok(Display {
+            width,
+            height,
+            planes: std::array::from_fn(|_| vec![0; height]),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Pixel at (x, y) as a plane bitmask, 0 if not set in any plane
+    pub fn pixel(&self, x: usize, y: usize) -> u8 {
+        let shift = MAX_DISPLAY_WIDTH - 1 - x;
+        self.planes
+            .iter()
+            .enumerate()
+            .fold(0, |pixel, (plane, rows)| {
+                pixel | ((rows[y] >> shift) as u8 & 0x1) << plane
+            })
+    }
+
+    /// Rows of the plane with the given index, from top to bottom
+    pub fn plane(&self, plane: usize) -> &[Row] {
+        &self.planes[plane]
+    }
+
+    /// Replace a row of the plane with the given index, returns whether it changed
+    pub fn set_row(&mut self, plane: usize, y: usize, row: Row) -> bool {
+        let row = row & self.row_mask();
+        let old = std::mem::replace(&mut self.planes[plane][y], row);
+        old != row
+    }
+
+    /// All pixels as plane bitmasks, one byte each in row-major order
+    pub fn to_pixels(&self) -> Vec<u8> {
+        let mut pixels = vec![0; self.width * self.height];
+        for (plane, rows) in self.planes.iter().enumerate() {
+            for (&row, line) in rows.iter().zip(pixels.chunks_mut(self.width)) {
+                for (x, pixel) in line.iter_mut().enumerate() {
+                    *pixel |= ((row >> (MAX_DISPLAY_WIDTH - 1 - x)) as u8 & 0x1) << plane;
+                }
+            }
+        }
+        pixels
+    }
+
+    /// Whether no pixel is set in any plane
+    pub fn is_clear(&self) -> bool {
+        self.planes.iter().flatten().all(|&row| row == 0)
+    }
+
+    /// Switch resolution, clearing the display
+    pub fn set_resolution(&mut self, width: usize, height: usize) {
+        *self = Display::new(width, height);
+    }
+
+    /// Clear the planes selected by the mask
+    pub fn clear(&mut self, planes: u8) {
+        for rows in self.selected(planes) {
+            rows.fill(0);
+        }
+    }
+
+    /// XOR a sprite row of up to 16 pixels in at (x, y) in the given plane,
+    /// returns true if any pixel that was set got cleared
+    ///
+    /// Pixels past the right edge wrap around to the left, or are clipped
+    /// when `wrap` is false.
+    pub fn draw_row(
+        &mut self,
+        x: usize,
+        y: usize,
+        sprite: u16,
+        sprite_width: usize,
+        plane: u8,
+        wrap: bool,
+    ) -> bool {
+        let sprite = (sprite as Row) << (MAX_DISPLAY_WIDTH - sprite_width);
+        let mut bits = sprite >> x;
+        if wrap && x + sprite_width > self.width {
+            bits |= sprite << (self.width - x);
+        }
+        bits &= self.row_mask();
+
+        let row = &mut self.planes[plane.trailing_zeros() as usize][y];
+        let collision = *row & bits != 0;
+        *row ^= bits;
+        collision
+    }
+
+    /// Scroll the selected planes down by n rows, clearing the rows at the top
+    pub fn scroll_down(&mut self, n: usize, planes: u8) {
+        let shift = n.min(self.height);
+        for rows in self.selected(planes) {
+            rows.rotate_right(shift);
+            rows[..shift].fill(0);
+        }
+    }
+
+    /// Scroll the selected planes up by n rows, clearing the rows at the bottom
+    pub fn scroll_up(&mut self, n: usize, planes: u8) {
+        let shift = n.min(self.height);
+        for rows in self.selected(planes) {
+            let len = rows.len();
+            rows.rotate_left(shift);
+            rows[len - shift..].fill(0);
+        }
+    }
+
+    /// Scroll the selected planes right by n columns, clearing the columns on the left
+    pub fn scroll_right(&mut self, n: usize, planes: u8) {
+        let mask = self.row_mask();
+        for row in self.selected(planes).flatten() {
+            *row = row.checked_shr(n as u32).unwrap_or(0) & mask;
+        }
+    }
+
+    /// Scroll the selected planes left by n columns, clearing the columns on the right
+    pub fn scroll_left(&mut self, n: usize, planes: u8) {
+        let mask = self.row_mask();
+        for row in self.selected(planes).flatten() {
+            *row = row.checked_shl(n as u32).unwrap_or(0) & mask;
+        }
+    }
+
+    /// Bits of a row that hold pixels
+    fn row_mask(&self) -> Row {
+        Row::MAX
+            .checked_shl((MAX_DISPLAY_WIDTH - self.width) as u32)
+            .unwrap_or(0)
+    }
+
+    /// Rows of the planes selected by the mask
+    fn selected(&mut self, planes: u8) -> impl Iterator<Item = &mut Vec<Row>> {
+        self.planes
+            .iter_mut()
+            .enumerate()
+            .filter(move |(plane, _)| planes & (1 << plane) != 0)
+            .map(|(_, rows)| rows)
+    }
+}
+
+/// How MegaChip sprite pixels are combined with the pixels beneath them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// Sprite pixels replace the display
+    #[default]
+    Normal,
+    /// Sprite pixels drawn at 25% opacity
+    Alpha25,
+    /// Sprite pixels drawn at 50% opacity
+    Alpha50,
+    /// Sprite pixels drawn at 75% opacity
+    Alpha75,
+    /// Sprite colour added to the display
+    Add,
+    /// Sprite colour multiplied with the display
+    Multiply,
+}
+
+impl BlendMode {
+    /// Blend mode selected by 080N, if N is valid
+    pub fn from_index(index: u8) -> Option<BlendMode> {
+        match index {
+            0 => Some(BlendMode::Normal),
+            1 => Some(BlendMode::Alpha25),
+            2 => Some(BlendMode::Alpha50),
+            3 => Some(BlendMode::Alpha75),
+            4 => Some(BlendMode::Add),
+            5 => Some(BlendMode::Multiply),
+            _ => None,
+        }
+    }
+
+    /// Combine a sprite colour with the display colour beneath it, both RGBA
+    pub fn blend(&self, src: [u8; 4], dst: [u8; 4]) -> [u8; 4] {
+        match self {
+            BlendMode::Normal => src,
+            BlendMode::Alpha25 => combine(src, dst, |src, dst| (src + dst * 3) / 4),
+            BlendMode::Alpha50 => combine(src, dst, |src, dst| (src + dst) / 2),
+            BlendMode::Alpha75 => combine(src, dst, |src, dst| (src * 3 + dst) / 4),
+            BlendMode::Add => combine(src, dst, |src, dst| src + dst),
+            BlendMode::Multiply => combine(src, dst, |src, dst| src * dst / 0xFF),
+        }
+    }
+}
+
+/// Combine the colour channels of two RGBA colours, the result is opaque
+fn combine(src: [u8; 4], dst: [u8; 4], channel: impl Fn(u16, u16) -> u16) -> [u8; 4] {
+    let mut out = [0xFF; 4];
+    for ((out, &src), &dst) in out.iter_mut().zip(&src).zip(&dst).take(3) {
+        *out = channel(src as u16, dst as u16).min(0xFF) as u8;
+    }
+    out
+}
+
+/// MegaChip 256-colour framebuffer
+///
+/// Keeps the palette index drawn at each pixel for collision detection, and
+/// the blended RGBA colour shown on screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorDisplay {
+    width: usize,
+    height: usize,
+    /// Palette indices in row-major order, 0 where nothing was drawn
+    indices: Vec<u8>,
+    /// RGBA colours in row-major order, four bytes per pixel
+    rgba: Vec<u8>,
+}
+
+impl ColorDisplay {
+    pub fn new(width: usize, height: usize) -> ColorDisplay {
+        ColorDisplay {
+            width,
+            height,
+            indices: vec![0; width * height],
+            rgba: vec![0; width * height * 4],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Palette index last drawn at (x, y)
+    pub fn index(&self, x: usize, y: usize) -> u8 {
+        self.indices[y * self.width + x]
+    }
+
+    /// RGBA colour at (x, y)
+    pub fn color(&self, x: usize, y: usize) -> [u8; 4] {
+        let offset = (y * self.width + x) * 4;
+        let mut color = [0; 4];
+        color.copy_from_slice(&self.rgba[offset..offset + 4]);
+        color
+    }
+
+    /// All colours as RGBA bytes in row-major order
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    pub fn clear(&mut self) {
+        self.indices.fill(0);
+        self.rgba.fill(0);
+    }
+
+    /// Draw palette index with its colour at (x, y), returns the index drawn over
+    pub fn draw(&mut self, x: usize, y: usize, index: u8, color: [u8; 4], blend: BlendMode) -> u8 {
+        let pixel = y * self.width + x;
+        let previous = std::mem::replace(&mut self.indices[pixel], index);
+        let dst = self.color(x, y);
+        self.rgba[pixel * 4..pixel * 4 + 4].copy_from_slice(&blend.blend(color, dst));
+        previous
+    }
+}
+
+/// Width in pixels of a CHIP-8X colour zone
+pub const ZONE_WIDTH: usize = 8;
+
+/// Height in pixels of the colour zones set by BXY0
+pub const ZONE_HEIGHT: usize = 4;
+
+/// Number of CHIP-8X background colours cycled through by 02A0
+pub const BACKGROUND_COUNT: u8 = 4;
+
+/// Foreground colour of every zone after reset, red
+pub const DEFAULT_FOREGROUND: u8 = 1;
+
+/// CHIP-8X colour attribute layer on top of the monochrome display
+///
+/// Set pixels are shown in the foreground colour of the 8x1 pixel cell they
+/// lie in, unset pixels in the background colour. Foreground colours range
+/// from 0 to 7 and background colours from 0 to 3.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorZones {
+    columns: usize,
+    rows: usize,
+    /// Foreground colours of the cells in row-major order
+    colors: Vec<u8>,
+    background: u8,
+}
+
+impl ColorZones {
+    pub fn new(width: usize, height: usize) -> ColorZones {
+        // A partial zone covers the pixels past the last whole one
+        let columns = width.div_ceil(ZONE_WIDTH);
+        ColorZones {
+            columns,
+            rows: height,
+            colors: vec![DEFAULT_FOREGROUND; columns * height],
+            background: 0,
+        }
+    }
+
+    /// Foreground colour of pixel (x, y)
+    pub fn foreground(&self, x: usize, y: usize) -> u8 {
+        self.colors[y * self.columns + x / ZONE_WIDTH]
+    }
+
+    pub fn background(&self) -> u8 {
+        self.background
+    }
+
+    /// Switch to the next background colour
+    pub fn cycle_background(&mut self) {
+        self.background = (self.background + 1) % BACKGROUND_COUNT;
+    }
+
+    /// Set the foreground colour of the cell in zone column and pixel row, ignoring cells off screen
+    pub fn set_color(&mut self, column: usize, row: usize, color: u8) {
+        if column < self.columns && row < self.rows {
+            self.colors[row * self.columns + column] = color;
+        }
+    }
+}
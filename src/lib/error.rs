@@ -0,0 +1,99 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::display::MAX_DISPLAY_WIDTH;
+
+/// Errors raised while loading or running a CHIP-8 program
+///
+/// Execution errors carry the address of the faulting instruction and its raw opcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chip8Error {
+    /// Instruction could not be decoded
+    UnknownOpcode { address: u16, opcode: u16 },
+    /// Program counter points outside of memory
+    PcOutOfBounds { address: u16 },
+    /// Instruction accessed memory outside of RAM
+    MemoryOutOfBounds {
+        address: u16,
+        opcode: u16,
+        memory_address: usize,
+    },
+    /// Subroutine call with a full stack
+    StackOverflow { address: u16, opcode: u16 },
+    /// Return from subroutine with an empty stack
+    StackUnderflow { address: u16, opcode: u16 },
+    /// SYS machine code subroutine did not return within the instruction limit
+    MachineCodeLimit { address: u16, opcode: u16 },
+    /// ROM load address is outside of program memory
+    InvalidLoadAddress { address: u16 },
+    /// ROM does not fit in memory when loaded at the given address
+    RomTooLarge {
+        address: u16,
+        size: usize,
+        max_size: usize,
+    },
+    /// Display resolution is empty or wider than a framebuffer row
+    InvalidResolution { width: usize, height: usize },
+}
+
+impl fmt::Display for Chip8Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Chip8Error::UnknownOpcode { address, opcode } => write!(
+                f,
+                "Failed to decode instruction: 0x{:04X} at address 0x{:X}",
+                opcode, address
+            ),
+            Chip8Error::PcOutOfBounds { address } => {
+                write!(
+                    f,
+                    "Program counter out of bounds at address 0x{:X}",
+                    address
+                )
+            }
+            Chip8Error::MemoryOutOfBounds {
+                address,
+                opcode,
+                memory_address,
+            } => write!(
+                f,
+                "Instruction 0x{:04X} at address 0x{:X} accessed memory out of bounds at 0x{:X}",
+                opcode, address, memory_address
+            ),
+            Chip8Error::StackOverflow { address, opcode } => write!(
+                f,
+                "Stack overflow by instruction 0x{:04X} at address 0x{:X}",
+                opcode, address
+            ),
+            Chip8Error::StackUnderflow { address, opcode } => write!(
+                f,
+                "Stack underflow by instruction 0x{:04X} at address 0x{:X}",
+                opcode, address
+            ),
+            Chip8Error::MachineCodeLimit { address, opcode } => write!(
+                f,
+                "Machine code called by instruction 0x{:04X} at address 0x{:X} did not return",
+                opcode, address
+            ),
+            Chip8Error::InvalidLoadAddress { address } => {
+                write!(f, "Invalid ROM load address 0x{:X}", address)
+            }
+            Chip8Error::RomTooLarge {
+                address,
+                size,
+                max_size,
+            } => write!(
+                f,
+                "ROM of {} bytes loaded at address 0x{:X} exceeds maximum size of {} bytes",
+                size, address, max_size
+            ),
+            Chip8Error::InvalidResolution { width, height } => write!(
+                f,
+                "Invalid display resolution {}x{}, width must be 1 to {} pixels and height at least 1",
+                width, height, MAX_DISPLAY_WIDTH
+            ),
+        }
+    }
+}
+
+impl Error for Chip8Error {}
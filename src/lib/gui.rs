@@ -1,3 +1,6 @@
+pub mod audio;
 pub mod events;
+pub mod keymap;
+pub mod pacer;
 pub mod renderer;
 pub mod window;
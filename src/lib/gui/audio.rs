@@ -0,0 +1,146 @@
+use crate::constants::{AUDIO_PATTERN_SIZE, AUDIO_SAMPLE_RATE, BUZZER_FREQUENCY, BUZZER_VOLUME};
+use crate::state::{AudioPattern, Sample, SoundEvent};
+use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired};
+use sdl2::Sdl;
+use std::sync::Arc;
+
+/// Square wave generator used as the CHIP-8 buzzer
+///
+/// Plays the XO-CHIP audio pattern instead once a program has loaded one.
+pub struct SquareWave {
+    sample_rate: f32,
+    phase: f32,
+    volume: f32,
+    pattern: Option<AudioPattern>,
+}
+
+impl AudioCallback for SquareWave {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        for sample in out.iter_mut() {
+            let (high, phase_inc) = match &self.pattern {
+                None => (self.phase <= 0.5, BUZZER_FREQUENCY / self.sample_rate),
+                Some(pattern) => {
+                    let bits = (AUDIO_PATTERN_SIZE * 8) as f32;
+                    (
+                        pattern.bit((self.phase * bits) as usize),
+                        pattern.playback_rate() / bits / self.sample_rate,
+                    )
+                }
+            };
+            *sample = if high { self.volume } else { -self.volume };
+            self.phase = (self.phase + phase_inc) % 1.0;
+        }
+    }
+}
+
+/// Player of MegaChip digitised sounds, mixed alongside the buzzer
+pub struct SamplePlayer {
+    sample_rate: f32,
+    position: f32,
+    volume: f32,
+    sample: Option<Sample>,
+}
+
+impl AudioCallback for SamplePlayer {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        for out in out.iter_mut() {
+            *out = 0.0;
+            let Some(sample) = &self.sample else {
+                continue;
+            };
+
+            if self.position as usize >= sample.data.len() {
+                if !sample.looping || sample.data.is_empty() {
+                    self.sample = None;
+                    continue;
+                }
+                self.position %= sample.data.len() as f32;
+            }
+
+            let value = sample.data[self.position as usize] as f32;
+            *out = (value - 128.0) / 128.0 * self.volume;
+            self.position += sample.rate as f32 / self.sample_rate;
+        }
+    }
+}
+
+pub struct Audio {
+    device: AudioDevice<SquareWave>,
+    sample_device: AudioDevice<SamplePlayer>,
+    /// Sound most recently started on the sample device
+    sample: Option<Sample>,
+}
+
+impl Audio {
+    pub fn new(sdl_context: &Sdl) -> Audio {
+        let audio_subsystem = sdl_context.audio().unwrap();
+        let desired_spec = AudioSpecDesired {
+            freq: Some(AUDIO_SAMPLE_RATE),
+            channels: Some(1),
+            samples: None,
+        };
+
+        Audio {
+            device: audio_subsystem
+                .open_playback(None, &desired_spec, |spec| SquareWave {
+                    sample_rate: spec.freq as f32,
+                    phase: 0.0,
+                    volume: BUZZER_VOLUME,
+                    pattern: None,
+                })
+                .unwrap(),
+            sample_device: audio_subsystem
+                .open_playback(None, &desired_spec, |spec| SamplePlayer {
+                    sample_rate: spec.freq as f32,
+                    position: 0.0,
+                    volume: BUZZER_VOLUME,
+                    sample: None,
+                })
+                .unwrap(),
+            sample: None,
+        }
+    }
+
+    /// Start or stop the buzzer
+    pub fn update(&mut self, event: SoundEvent) {
+        match event {
+            SoundEvent::Start => self.device.resume(),
+            SoundEvent::Stop => self.device.pause(),
+        }
+    }
+
+    /// Play the XO-CHIP audio pattern, or the square wave if there is none
+    pub fn set_pattern(&mut self, pattern: Option<AudioPattern>) {
+        self.device.lock().pattern = pattern;
+    }
+
+    /// Play a MegaChip digitised sound from the start, or stop it if there is none
+    ///
+    /// Nothing happens if the sound is the one already playing.
+    pub fn play_sample(&mut self, sample: Option<&Sample>) {
+        let unchanged = match (&self.sample, sample) {
+            (Some(current), Some(sample)) => Arc::ptr_eq(&current.data, &sample.data),
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return;
+        }
+
+        self.sample = sample.cloned();
+        {
+            let mut player = self.sample_device.lock();
+            player.sample = self.sample.clone();
+            player.position = 0.0;
+        }
+
+        match self.sample {
+            Some(_) => self.sample_device.resume(),
+            None => self.sample_device.pause(),
+        }
+    }
+}
@@ -1,10 +1,15 @@
+use super::audio::Audio;
+use super::keymap::{map_scancode, map_second_keypad};
+use super::pacer::FramePacer;
 use super::renderer::Renderer;
+use crate::clock::{Clock, RealTimeClock};
+use crate::constants::{MAX_CATCH_UP_FRAMES, TIMER_FREQUENCY, WINDOW_TITLE};
+use crate::state::State;
 use crate::Chip8;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use sdl2::EventPump;
 use sdl2::Sdl;
-use std::thread::sleep;
 use std::time::Duration;
 
 pub struct Events {
@@ -18,7 +23,14 @@ impl Events {
         }
     }
 
-    pub fn handle_events(&mut self, chip8: &mut Chip8, renderer: &mut Renderer) {
+    pub fn handle_events(&mut self, chip8: &mut Chip8, renderer: &mut Renderer, audio: &mut Audio) {
+        let mut clock = RealTimeClock::new();
+        let max_lag = Duration::from_secs(1) / TIMER_FREQUENCY * MAX_CATCH_UP_FRAMES;
+        let mut pacer = FramePacer::new(TIMER_FREQUENCY);
+        let mut loops = 0;
+        // Last drawn state, presented every loop when vsync paces the loop
+        let mut shown: Option<State> = None;
+
         'running: loop {
             for event in self.event_pump.poll_iter() {
                 match event {
@@ -27,17 +39,98 @@ impl Events {
                         keycode: Some(Keycode::Escape),
                         ..
                     } => break 'running,
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Equals),
+                        ..
+                    } => {
+                        println!("Tick rate: {}", chip8.increase_tick_rate());
+                    }
+                    Event::KeyDown {
+                        keycode: Some(Keycode::Minus),
+                        ..
+                    } => {
+                        println!("Tick rate: {}", chip8.decrease_tick_rate());
+                    }
+                    Event::KeyDown {
+                        scancode: Some(scancode),
+                        repeat: false,
+                        ..
+                    } => {
+                        if let Some(key) = map_scancode(scancode) {
+                            chip8.key_down(key);
+                        } else if let Some(key) = map_second_keypad(scancode) {
+                            chip8.key2_down(key);
+                        }
+                    }
+                    Event::KeyUp {
+                        scancode: Some(scancode),
+                        ..
+                    } => {
+                        if let Some(key) = map_scancode(scancode) {
+                            chip8.key_up(key);
+                        } else if let Some(key) = map_second_keypad(scancode) {
+                            chip8.key2_up(key);
+                        }
+                    }
                     _ => {}
                 }
             }
 
-            let state = chip8.run_cycle();
+            // Skip time lost to host stalls instead of fast-forwarding through it
+            let lag = clock.now().saturating_sub(chip8.scheduled_time());
+            if lag > max_lag {
+                clock.skip(lag - max_lag);
+            }
+
+            let frame = match chip8.run_until(&clock) {
+                Ok(frame) => frame,
+                Err(err) => {
+                    println!("Emulation stopped: {}", err);
+                    break 'running;
+                }
+            };
+
+            if let Some(frame) = frame {
+                if frame.state.draw_flag {
+                    if !renderer.vsync() {
+                        render(renderer, &frame.state);
+                    }
+                    shown = Some(frame.state);
+                }
+                if let Some(sound_event) = frame.sound_event {
+                    audio.update(sound_event);
+                }
+            }
+
+            audio.set_pattern(chip8.audio_pattern());
+            audio.play_sample(chip8.sample());
 
-            if state.draw_flag {
-                renderer.update(&state.display_buffer);
+            // Presenting with vsync waits for the display refresh, so the
+            // pacer only measures the frame rate
+            if renderer.vsync() {
+                match &shown {
+                    Some(state) => render(renderer, state),
+                    None => renderer.clear_screen(),
+                }
+                pacer.record_frame();
+            } else {
+                pacer.wait();
             }
 
-            sleep(Duration::new(0, 16_666_666));
+            // Show the measured frame rate about once a second
+            loops += 1;
+            if loops % TIMER_FREQUENCY == 0 {
+                renderer.set_title(&format!("{} - {:.1} FPS", WINDOW_TITLE, pacer.fps()));
+            }
         }
     }
 }
+
+/// Draw the display of the state in its colours
+fn render(renderer: &mut Renderer, state: &State) {
+    match (&state.color_buffer, &state.color_zones) {
+        (Some(color_buffer), _) => renderer.update_rgba(color_buffer),
+        (None, Some(color_zones)) => renderer.update_zones(&state.display_buffer, color_zones),
+        (None, None) => renderer.update(&state.display_buffer),
+    }
+}
@@ -0,0 +1,66 @@
+use sdl2::keyboard::Scancode;
+
+/// Map keyboard to the 16 key hex keypad
+///
+/// Scancodes are used so the layout stays in the same physical position
+/// regardless of the keyboard layout:
+///
+/// ```text
+/// Keyboard       CHIP-8 Keypad
+/// 1 2 3 4        1 2 3 C
+/// Q W E R   ->   4 5 6 D
+/// A S D F        7 8 9 E
+/// Z X C V        A 0 B F
+/// ```
+pub fn map_scancode(scancode: Scancode) -> Option<u8> {
+    match scancode {
+        Scancode::Num1 => Some(0x1),
+        Scancode::Num2 => Some(0x2),
+        Scancode::Num3 => Some(0x3),
+        Scancode::Num4 => Some(0xC),
+        Scancode::Q => Some(0x4),
+        Scancode::W => Some(0x5),
+        Scancode::E => Some(0x6),
+        Scancode::R => Some(0xD),
+        Scancode::A => Some(0x7),
+        Scancode::S => Some(0x8),
+        Scancode::D => Some(0x9),
+        Scancode::F => Some(0xE),
+        Scancode::Z => Some(0xA),
+        Scancode::X => Some(0x0),
+        Scancode::C => Some(0xB),
+        Scancode::V => Some(0xF),
+        _ => None,
+    }
+}
+
+/// Map the numeric keypad to the CHIP-8X second hex keypad
+///
+/// ```text
+/// Numeric Keypad     CHIP-8 Keypad
+/// 7 8 9 /            1 2 3 C
+/// 4 5 6 *      ->    4 5 6 D
+/// 1 2 3 -            7 8 9 E
+/// 0 . Enter +        A 0 B F
+/// ```
+pub fn map_second_keypad(scancode: Scancode) -> Option<u8> {
+    match scancode {
+        Scancode::Kp7 => Some(0x1),
+        Scancode::Kp8 => Some(0x2),
+        Scancode::Kp9 => Some(0x3),
+        Scancode::KpDivide => Some(0xC),
+        Scancode::Kp4 => Some(0x4),
+        Scancode::Kp5 => Some(0x5),
+        Scancode::Kp6 => Some(0x6),
+        Scancode::KpMultiply => Some(0xD),
+        Scancode::Kp1 => Some(0x7),
+        Scancode::Kp2 => Some(0x8),
+        Scancode::Kp3 => Some(0x9),
+        Scancode::KpMinus => Some(0xE),
+        Scancode::Kp0 => Some(0xA),
+        Scancode::KpPeriod => Some(0x0),
+        Scancode::KpEnter => Some(0xB),
+        Scancode::KpPlus => Some(0xF),
+        _ => None,
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use crate::clock::{Clock, RealTimeClock};
+
+/// Period over which the actual frame rate is measured
+const FPS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Limits the main loop to a fixed frame rate
+///
+/// Waits on its clock until each deadline, which the real-time clock does by
+/// sleeping and then spinning. Deadlines advance by exactly one frame, so
+/// time spent emulating and rendering does not add up to drift.
+pub struct FramePacer<C: Clock = RealTimeClock> {
+    clock: C,
+    frame_duration: Duration,
+    deadline: Duration,
+    /// How late the last frame started
+    drift: Duration,
+    frames: u32,
+    fps_start: Duration,
+    fps: f64,
+}
+
+impl FramePacer {
+    /// Pacer running in real time
+    pub fn new(frame_rate: u32) -> FramePacer {
+        FramePacer::with_clock(frame_rate, RealTimeClock::new())
+    }
+}
+
+impl<C: Clock> FramePacer<C> {
+    /// Pacer scheduling frames against the given clock
+    pub fn with_clock(frame_rate: u32, clock: C) -> FramePacer<C> {
+        let now = clock.now();
+        let frame_duration = Duration::from_secs(1) / frame_rate;
+        FramePacer {
+            clock,
+            frame_duration,
+            deadline: now + frame_duration,
+            drift: Duration::ZERO,
+            frames: 0,
+            fps_start: now,
+            fps: frame_rate as f64,
+        }
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    pub fn clock_mut(&mut self) -> &mut C {
+        &mut self.clock
+    }
+
+    /// Block until the next frame is due
+    pub fn wait(&mut self) {
+        self.clock.wait_until(self.deadline);
+
+        let now = self.clock.now();
+        self.drift = now.saturating_sub(self.deadline);
+
+        // Start over from now after falling more than a frame behind, rather
+        // than running frames back to back to catch up
+        self.deadline += self.frame_duration;
+        if self.deadline < now {
+            self.deadline = now + self.frame_duration;
+        }
+
+        self.record_frame();
+    }
+
+    /// Count a frame paced by something else, such as presenting with vsync
+    pub fn record_frame(&mut self) {
+        let now = self.clock.now();
+        self.frames += 1;
+        let elapsed = now - self.fps_start;
+        if elapsed >= FPS_INTERVAL {
+            self.fps = self.frames as f64 / elapsed.as_secs_f64();
+            self.frames = 0;
+            self.fps_start = now;
+        }
+    }
+
+    /// How late the last frame started after its deadline
+    pub fn drift(&self) -> Duration {
+        self.drift
+    }
+
+    /// Frames per second measured over the last second
+    pub fn fps(&self) -> f64 {
+        self.fps
+    }
+}
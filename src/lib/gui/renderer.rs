@@ -1,49 +1,173 @@
 use super::window::Window;
-use crate::constants::{DISPLAY_HEIGHT, DISPLAY_SCALE, DISPLAY_WIDTH};
-use sdl2::pixels::Color;
+use crate::display::{ColorDisplay, ColorZones, Display, Row, MAX_DISPLAY_WIDTH, PLANE_COUNT};
+use sdl2::pixels::{Color, PixelFormatEnum};
 use sdl2::rect::Rect;
-use sdl2::render::Canvas;
+use sdl2::render::{Canvas, Texture, TextureCreator};
+use sdl2::video::WindowContext;
+
+/// Colours for pixels set in neither plane, the first, the second and both
+pub const DEFAULT_PALETTE: [Color; 4] = [
+    Color::RGB(0x00, 0x00, 0x00),
+    Color::RGB(0xFF, 0xFF, 0xFF),
+    Color::RGB(0xAA, 0xAA, 0xAA),
+    Color::RGB(0x55, 0x55, 0x55),
+];
+
+/// CHIP-8X background colours: blue, black, green and red
+pub const CHIP8X_BACKGROUND: [Color; 4] = [
+    Color::RGB(0x00, 0x00, 0x80),
+    Color::RGB(0x00, 0x00, 0x00),
+    Color::RGB(0x00, 0x80, 0x00),
+    Color::RGB(0x80, 0x00, 0x00),
+];
+
+/// CHIP-8X foreground colours of the VP-590 colour board
+pub const CHIP8X_FOREGROUND: [Color; 8] = [
+    Color::RGB(0x00, 0x00, 0x00),
+    Color::RGB(0xFF, 0x00, 0x00),
+    Color::RGB(0x00, 0x00, 0xFF),
+    Color::RGB(0xFF, 0x00, 0xFF),
+    Color::RGB(0x00, 0xFF, 0x00),
+    Color::RGB(0xFF, 0xFF, 0x00),
+    Color::RGB(0x00, 0xFF, 0xFF),
+    Color::RGB(0xFF, 0xFF, 0xFF),
+];
 
 pub struct Renderer {
     canvas: Canvas<sdl2::video::Window>,
+    /// Creator of the textures, kept for the rest of the program since
+    /// textures borrow it
+    texture_creator: &'static TextureCreator<WindowContext>,
+    /// MegaChip colour display texture, reused while its size is unchanged
+    color_texture: Option<Texture<'static>>,
+    palette: [Color; 4],
+    vsync: bool,
 }
 
 impl Renderer {
-    pub fn new(window: Window) -> Renderer {
+    /// Create a renderer for the window, presenting in sync with the display refresh if vsync is set
+    pub fn new(window: Window, vsync: bool) -> Renderer {
         let sdl_window = window.sdl_window;
+        let mut canvas_builder = sdl_window.into_canvas();
+        if vsync {
+            canvas_builder = canvas_builder.present_vsync();
+        }
+
+        let canvas = canvas_builder.build().map_err(|e| e.to_string()).unwrap();
+        let texture_creator = Box::leak(Box::new(canvas.texture_creator()));
+
         Renderer {
-            canvas: sdl_window
-                .into_canvas()
-                .build()
-                .map_err(|e| e.to_string())
-                .unwrap(),
+            canvas,
+            texture_creator,
+            color_texture: None,
+            palette: DEFAULT_PALETTE,
+            vsync,
         }
     }
 
+    /// Whether presenting waits for the display refresh
+    pub fn vsync(&self) -> bool {
+        self.vsync
+    }
+
+    /// Replace the window title, used to show the frame rate
+    pub fn set_title(&mut self, title: &str) {
+        self.canvas.window_mut().set_title(title).unwrap();
+    }
+
+    /// Set the colours pixels are drawn in, indexed by their plane bitmask
+    pub fn set_palette(&mut self, palette: [Color; 4]) {
+        self.palette = palette;
+    }
+
     pub fn clear_screen(&mut self) {
-        self.canvas.set_draw_color(Color::BLACK);
+        self.canvas.set_draw_color(self.palette[0]);
         self.canvas.clear();
         self.canvas.present();
     }
 
-    pub fn update(&mut self, display_buffer: &[[u8; DISPLAY_HEIGHT]; DISPLAY_WIDTH]) {
-        self.canvas.set_draw_color(Color::WHITE);
-
-        for (x, row) in display_buffer.iter().enumerate() {
-            for (y, cell) in row.iter().enumerate() {
-                if *cell == 1 {
-                    self.canvas
-                        .fill_rect(Rect::new(
-                            (x * DISPLAY_SCALE) as i32,
-                            (y * DISPLAY_SCALE) as i32,
-                            DISPLAY_SCALE as u32,
-                            DISPLAY_SCALE as u32,
-                        ))
-                        .unwrap();
-                }
+    pub fn update(&mut self, display_buffer: &Display) {
+        let palette = self.palette;
+        self.draw(display_buffer, palette[0], |_, _, cell| {
+            palette[cell as usize % palette.len()]
+        });
+    }
+
+    /// Draw the display in the CHIP-8X zone colours
+    pub fn update_zones(&mut self, display_buffer: &Display, color_zones: &ColorZones) {
+        let background = CHIP8X_BACKGROUND[color_zones.background() as usize];
+        self.draw(display_buffer, background, |x, y, _| {
+            CHIP8X_FOREGROUND[color_zones.foreground(x, y) as usize]
+        });
+    }
+
+    /// Clear to the background colour and draw each set pixel in its colour
+    fn draw(
+        &mut self,
+        display_buffer: &Display,
+        background: Color,
+        color: impl Fn(usize, usize, u8) -> Color,
+    ) {
+        self.canvas.set_draw_color(background);
+        self.canvas.clear();
+
+        // Scale pixels to fill the window in both low and high resolution
+        let (window_width, window_height) = self.canvas.output_size().unwrap();
+        let scale_x = window_width / display_buffer.width() as u32;
+        let scale_y = window_height / display_buffer.height() as u32;
+
+        for y in 0..display_buffer.height() {
+            let rows: [Row; PLANE_COUNT] =
+                std::array::from_fn(|plane| display_buffer.plane(plane)[y]);
+
+            // Visit only the pixels set in some plane, leftmost first
+            let mut set = rows.iter().fold(0, |set, row| set | row);
+            while set != 0 {
+                let x = set.leading_zeros() as usize;
+                let bit: Row = 1 << (MAX_DISPLAY_WIDTH - 1 - x);
+                set &= !bit;
+
+                let cell = rows.iter().enumerate().fold(0, |cell, (plane, row)| {
+                    cell | ((row & bit != 0) as u8) << plane
+                });
+                self.canvas.set_draw_color(color(x, y, cell));
+                self.canvas
+                    .fill_rect(Rect::new(
+                        x as i32 * scale_x as i32,
+                        y as i32 * scale_y as i32,
+                        scale_x,
+                        scale_y,
+                    ))
+                    .unwrap();
             }
         }
 
         self.canvas.present();
     }
+
+    /// Draw the MegaChip colour display, stretched to fill the window
+    pub fn update_rgba(&mut self, color_display: &ColorDisplay) {
+        let (width, height) = (color_display.width(), color_display.height());
+        let texture = match &mut self.color_texture {
+            Some(texture)
+                if texture.query().width == width as u32
+                    && texture.query().height == height as u32 =>
+            {
+                texture
+            }
+            color_texture => color_texture.insert(
+                self.texture_creator
+                    .create_texture_streaming(PixelFormatEnum::RGBA32, width as u32, height as u32)
+                    .unwrap(),
+            ),
+        };
+        texture
+            .update(None, color_display.rgba(), width * 4)
+            .unwrap();
+
+        self.canvas.set_draw_color(self.palette[0]);
+        self.canvas.clear();
+        self.canvas.copy(texture, None, None).unwrap();
+        self.canvas.present();
+    }
 }
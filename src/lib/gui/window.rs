@@ -1,4 +1,4 @@
-use crate::constants::{DISPLAY_HEIGHT, DISPLAY_SCALE, DISPLAY_WIDTH, WINDOW_TITLE};
+use crate::constants::{DISPLAY_SCALE, WINDOW_TITLE};
 use sdl2::video::Window as SdlWindow;
 use sdl2::Sdl;
 
@@ -7,14 +7,15 @@ pub struct Window {
 }
 
 impl Window {
-    pub fn new(sdl_context: &Sdl) -> Window {
+    /// Create a window scaled for a display of the given size
+    pub fn new(sdl_context: &Sdl, display_width: usize, display_height: usize) -> Window {
         let video_subsystem = sdl_context.video().unwrap();
         Window {
             sdl_window: video_subsystem
                 .window(
                     WINDOW_TITLE,
-                    (DISPLAY_WIDTH * DISPLAY_SCALE) as u32,
-                    (DISPLAY_HEIGHT * DISPLAY_SCALE) as u32,
+                    (display_width * DISPLAY_SCALE) as u32,
+                    (display_height * DISPLAY_SCALE) as u32,
                 )
                 .position_centered()
                 .opengl()
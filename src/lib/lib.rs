@@ -1,22 +1,315 @@
 pub mod cpu;
 pub mod gui;
 
-mod constants;
-mod state;
+pub mod cdp1802;
+pub mod clock;
+pub mod constants;
+pub mod display;
+pub mod error;
+pub mod font;
+pub mod machine;
+pub mod platform;
+pub mod quirks;
+pub mod state;
+pub mod timing;
+pub mod vip;
 
-use cpu::Cpu;
-use state::State;
+use std::time::Duration;
+
+use clock::Clock;
+use constants::TIMER_FREQUENCY;
+use cpu::{BlockRun, Cpu, Engine};
+use error::Chip8Error;
+use machine::MachineConfig;
+use platform::Platform;
+use state::{AudioPattern, Frame, Sample, SoundEvent, State};
+use timing::{TimingMode, TICK_RATES, VIP_FRAME_CYCLES};
 
 pub struct Chip8 {
     cpu: Cpu,
+    platform: Platform,
+    timing: TimingMode,
+    engine: Engine,
+    /// Machine cycles the last frame overran its budget by in cycle-accurate mode
+    cycle_debt: i32,
+    /// Clock time up to which frames have been run
+    scheduled_time: Duration,
 }
 
 impl Chip8 {
-    pub fn new(rom: &[u8]) -> Chip8 {
-        Chip8 { cpu: Cpu::new(rom) }
+    /// Load ROM at the program start of the platform
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ROM does not fit in memory, use [`Chip8::load_rom`] to handle the error
+    pub fn new(rom: &[u8], platform: Platform) -> Chip8 {
+        Chip8::load_rom(rom, platform.program_start(), platform)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 
-    pub fn run_cycle(&mut self) -> State {
+    /// Load ROM at the given address, which is also the entry point
+    ///
+    /// Fails if the address is outside program memory or the ROM does not fit
+    pub fn load_rom(
+        rom: &[u8],
+        load_address: u16,
+        platform: Platform,
+    ) -> Result<Chip8, Chip8Error> {
+        let config = MachineConfig {
+            load_address,
+            ..platform.machine_config()
+        };
+        let cpu = Cpu::try_new(rom, config)?;
+
+        Ok(Chip8 {
+            cpu,
+            platform,
+            timing: TimingMode::InstructionsPerFrame(platform.tick_rate()),
+            engine: Engine::Interpreter,
+            cycle_debt: 0,
+            scheduled_time: Duration::ZERO,
+        })
+    }
+
+    /// Interpreter being emulated
+    pub fn platform(&self) -> Platform {
+        self.platform
+    }
+
+    /// Interpreter state, for inspecting registers and memory
+    pub fn cpu(&self) -> &Cpu {
+        &self.cpu
+    }
+
+    pub fn run_cycle(&mut self) -> Result<State, Chip8Error> {
         self.cpu.run_cycle()
     }
+
+    /// How many instructions run per frame, the platform tick rate by default
+    pub fn timing(&self) -> TimingMode {
+        self.timing
+    }
+
+    pub fn set_timing(&mut self, timing: TimingMode) {
+        self.timing = timing;
+        self.cycle_debt = 0;
+    }
+
+    /// How frames run instructions, the interpreter by default
+    pub fn engine(&self) -> Engine {
+        self.engine
+    }
+
+    pub fn set_engine(&mut self, engine: Engine) {
+        self.engine = engine;
+    }
+
+    /// Instructions run per frame, None in cycle-accurate mode
+    pub fn tick_rate(&self) -> Option<u32> {
+        match self.timing {
+            TimingMode::InstructionsPerFrame(count) => Some(count),
+            TimingMode::CycleAccurate => None,
+        }
+    }
+
+    /// Run a fixed number of instructions per frame
+    pub fn set_tick_rate(&mut self, tick_rate: u32) {
+        self.set_timing(TimingMode::InstructionsPerFrame(tick_rate));
+    }
+
+    /// Switch to the next faster tick rate preset, returns the new tick rate
+    pub fn increase_tick_rate(&mut self) -> u32 {
+        let current = self.tick_rate().unwrap_or(0);
+        let tick_rate = TICK_RATES
+            .into_iter()
+            .find(|&rate| rate > current)
+            .unwrap_or(TICK_RATES[TICK_RATES.len() - 1]);
+        self.set_tick_rate(tick_rate);
+        tick_rate
+    }
+
+    /// Switch to the next slower tick rate preset, returns the new tick rate
+    pub fn decrease_tick_rate(&mut self) -> u32 {
+        let current = self.tick_rate().unwrap_or(u32::MAX);
+        let tick_rate = TICK_RATES
+            .into_iter()
+            .rev()
+            .find(|&rate| rate < current)
+            .unwrap_or(TICK_RATES[0]);
+        self.set_tick_rate(tick_rate);
+        tick_rate
+    }
+
+    /// Clock time up to which frames have been run
+    pub fn scheduled_time(&self) -> Duration {
+        self.scheduled_time
+    }
+
+    /// Run the frames due by the clock's time, returns their combined result if any ran
+    ///
+    /// Each frame is 1/60 s of clock time and every due frame is run, so a
+    /// virtual clock gives the same run on any host however it is advanced.
+    pub fn run_until(&mut self, clock: &impl Clock) -> Result<Option<Frame>, Chip8Error> {
+        let time = clock.now();
+        let frame_duration = Duration::from_secs(1) / TIMER_FREQUENCY;
+
+        let sound_playing = self.cpu.is_sound_playing();
+        let mut state: Option<State> = None;
+        while self.scheduled_time + frame_duration <= time {
+            self.scheduled_time += frame_duration;
+
+            // Keep the last frame that drew, its display is the newest
+            let frame = self.run_frame()?;
+            if frame.state.draw_flag || !state.as_ref().is_some_and(|state| state.draw_flag) {
+                state = Some(frame.state);
+            }
+        }
+
+        let sound_event = match self.cpu.is_sound_playing() {
+            playing if playing == sound_playing => None,
+            true => Some(SoundEvent::Start),
+            false => Some(SoundEvent::Stop),
+        };
+
+        Ok(state.map(|state| Frame { state, sound_event }))
+    }
+
+    /// Run the instructions of one 60 Hz frame, then tick the timers once
+    ///
+    /// Instructions end early while execution is halted, such as by a sprite
+    /// draw waiting for the display refresh.
+    pub fn run_frame(&mut self) -> Result<Frame, Chip8Error> {
+        match self.timing {
+            TimingMode::InstructionsPerFrame(count) => {
+                let mut remaining = count;
+                while remaining > 0 {
+                    match self.run_instructions(remaining, u32::MAX)? {
+                        BlockRun {
+                            instructions: 0, ..
+                        } => break,
+                        run => remaining -= run.instructions,
+                    }
+                }
+            }
+            TimingMode::CycleAccurate => {
+                // Instructions overrunning the frame take cycles from the next one
+                let mut budget = VIP_FRAME_CYCLES - self.cycle_debt;
+                while budget > 0 {
+                    match self.run_instructions(u32::MAX, budget as u32)? {
+                        BlockRun {
+                            instructions: 0, ..
+                        } => break,
+                        run => budget -= run.cycles as i32,
+                    }
+                }
+                self.cycle_debt = (-budget).max(0);
+            }
+        }
+
+        Ok(Frame {
+            state: self.cpu.state(),
+            sound_event: self.cpu.tick_timers(),
+        })
+    }
+
+    /// Run instructions with the selected engine, the interpreter runs a
+    /// single one per call
+    fn run_instructions(
+        &mut self,
+        max_instructions: u32,
+        max_cycles: u32,
+    ) -> Result<BlockRun, Chip8Error> {
+        match self.engine {
+            Engine::Interpreter => {
+                let cycles = self.cpu.step()?;
+                Ok(BlockRun {
+                    instructions: (cycles > 0) as u32,
+                    cycles,
+                })
+            }
+            Engine::Threaded => self.cpu.run_block(max_instructions, max_cycles),
+            Engine::Native(program) => program(&mut self.cpu, max_instructions, max_cycles),
+        }
+    }
+
+    /// Width of the display outside SUPER-CHIP high resolution mode
+    pub fn display_width(&self) -> usize {
+        self.cpu.display_width()
+    }
+
+    /// Height of the display outside SUPER-CHIP high resolution mode
+    pub fn display_height(&self) -> usize {
+        self.cpu.display_height()
+    }
+
+    /// Whether the program stopped the interpreter with EXIT
+    pub fn is_halted(&self) -> bool {
+        self.cpu.is_halted()
+    }
+
+    /// Return addresses on the call stack, oldest first
+    pub fn call_stack(&self) -> &[u16] {
+        self.cpu.call_stack()
+    }
+
+    /// Press key on the hex keypad
+    pub fn key_down(&mut self, key: u8) {
+        self.cpu.key_down(key);
+    }
+
+    /// Release key on the hex keypad
+    pub fn key_up(&mut self, key: u8) {
+        self.cpu.key_up(key);
+    }
+
+    /// Press key on the CHIP-8X second keypad
+    pub fn key2_down(&mut self, key: u8) {
+        self.cpu.key2_down(key);
+    }
+
+    /// Release key on the CHIP-8X second keypad
+    pub fn key2_up(&mut self, key: u8) {
+        self.cpu.key2_up(key);
+    }
+
+    /// Value last written to the CHIP-8X output port
+    pub fn io_output(&self) -> u8 {
+        self.cpu.io_output
+    }
+
+    /// Latch the value read from the CHIP-8X input port
+    pub fn set_io_input(&mut self, value: u8) {
+        self.cpu.io_input = value;
+    }
+
+    /// Whether emulation is halted waiting for a key press and release
+    pub fn is_waiting_for_key(&self) -> bool {
+        self.cpu.is_waiting_for_key()
+    }
+
+    /// XO-CHIP audio pattern played instead of the buzzer, if the program loaded one
+    pub fn audio_pattern(&self) -> Option<AudioPattern> {
+        self.cpu.audio_pattern.map(|pattern| AudioPattern {
+            pattern,
+            pitch: self.cpu.pitch,
+        })
+    }
+
+    /// MegaChip digitised sound currently playing, if any
+    pub fn sample(&self) -> Option<&Sample> {
+        self.cpu.sample.as_ref()
+    }
+
+    /// Reseed the random number generator used by the RND instruction
+    pub fn seed_rng(&mut self, seed: u32) {
+        self.cpu.seed_rng(seed);
+    }
+
+    /// Decrement delay and sound timers, for callers running instructions with
+    /// [`Chip8::run_cycle`] who must call it at 60 Hz independent of cycles run
+    ///
+    /// [`Chip8::run_frame`] already ticks the timers once per frame.
+    pub fn tick_timers(&mut self) -> Option<SoundEvent> {
+        self.cpu.tick_timers()
+    }
 }
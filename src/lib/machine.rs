@@ -0,0 +1,63 @@
+use super::constants::{DISPLAY_HEIGHT, DISPLAY_WIDTH, MEMORY_SIZE, PROGRAM_START, STACK_SIZE};
+use super::cpu::{InstructionSet, StackPolicy};
+use super::font::FontSet;
+use super::quirks::Quirks;
+
+/// Where the interpreter keeps its call stack, registers and framebuffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryLayout {
+    /// Kept outside of RAM, programs can use all memory
+    #[default]
+    Separate,
+    /// Mirrored in the top of RAM like the COSMAC VIP interpreter, with the
+    /// stack at 0xEA0, V registers at 0xEF0 and the 64x32 framebuffer at
+    /// 0xF00 in 4 KiB of RAM, so programs can read and write them
+    Vip,
+}
+
+/// Geometry and behaviour of the emulated machine, consumed by [`Cpu::new`]
+///
+/// The default is a 4 KiB CHIP-8 machine with a 16 level stack and a 64x32
+/// display, loading programs at 0x200.
+///
+/// [`Cpu::new`]: crate::cpu::Cpu::new
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachineConfig {
+    /// Size of RAM in bytes
+    pub memory_size: usize,
+    /// Maximum number of nested subroutine calls
+    pub stack_depth: usize,
+    /// What happens when a call exceeds the stack depth
+    pub stack_policy: StackPolicy,
+    /// Display width outside SUPER-CHIP high resolution mode, 1 to 128 pixels
+    pub display_width: usize,
+    /// Display height outside SUPER-CHIP high resolution mode, at least 1 pixel
+    pub display_height: usize,
+    pub memory_layout: MemoryLayout,
+    /// Address the ROM is loaded at, which is also the entry point
+    pub load_address: u16,
+    pub quirks: Quirks,
+    pub instruction_set: InstructionSet,
+    /// Hex digit sprites loaded at the start of memory
+    pub font: FontSet,
+    /// Seed of the RND generator, fixed so runs are reproducible
+    pub rng_seed: u32,
+}
+
+impl Default for MachineConfig {
+    fn default() -> MachineConfig {
+        MachineConfig {
+            memory_size: MEMORY_SIZE,
+            stack_depth: STACK_SIZE,
+            stack_policy: StackPolicy::Error,
+            display_width: DISPLAY_WIDTH,
+            display_height: DISPLAY_HEIGHT,
+            memory_layout: MemoryLayout::Separate,
+            load_address: PROGRAM_START,
+            quirks: Quirks::default(),
+            instruction_set: InstructionSet::Chip8,
+            font: FontSet::Chip48,
+            rng_seed: 0,
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::constants::{
+    DISPLAY_HEIGHT, DISPLAY_WIDTH, MEGA_MEMORY_SIZE, MEMORY_SIZE, PROGRAM_START,
+    TWO_PAGE_DISPLAY_HEIGHT, XO_MEMORY_SIZE,
+};
+use super::cpu::InstructionSet;
+use super::font::FontSet;
+use super::machine::MachineConfig;
+use super::quirks::{MemoryIncrement, Quirks};
+
+/// Well-known CHIP-8 interpreters, bundling their quirks and machine settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Platform {
+    /// Behaviour described by Cowgod's Chip-8 Technical Reference
+    #[default]
+    Cowgod,
+    /// Original interpreter of the RCA COSMAC VIP
+    CosmacVip,
+    /// Hi-res CHIP-8 for the COSMAC VIP with a 64x64 two-page display
+    HiRes,
+    /// CHIP-8E for the COSMAC VIP
+    Chip8E,
+    /// CHIP-48 for the HP 48 calculators
+    Chip48,
+    /// SUPER-CHIP 1.0 for the HP 48 calculators
+    SuperChip10,
+    /// SUPER-CHIP 1.1 for the HP 48 calculators
+    SuperChip11,
+    /// Behaviour of modern interpreters such as Octo for plain CHIP-8 programs
+    Modern,
+    /// XO-CHIP extension introduced by Octo
+    XoChip,
+    /// MegaChip8 extension of SUPER-CHIP with a 256-colour display
+    MegaChip,
+    /// CHIP-8X for the COSMAC VIP with the VP-590 colour and VP-595 sound boards
+    Chip8X,
+}
+
+impl Platform {
+    /// All presets, in order of introduction
+    pub const ALL: [Platform; 11] = [
+        Platform::Cowgod,
+        Platform::CosmacVip,
+        Platform::HiRes,
+        Platform::Chip8E,
+        Platform::Chip48,
+        Platform::SuperChip10,
+        Platform::SuperChip11,
+        Platform::Modern,
+        Platform::XoChip,
+        Platform::MegaChip,
+        Platform::Chip8X,
+    ];
+
+    /// Short name used to select the platform
+    pub fn name(&self) -> &'static str {
+        match self {
+            Platform::Cowgod => "cowgod",
+            Platform::CosmacVip => "vip",
+            Platform::HiRes => "hires",
+            Platform::Chip8E => "chip8e",
+            Platform::Chip8X => "chip8x",
+            Platform::Chip48 => "chip48",
+            Platform::SuperChip10 => "schip10",
+            Platform::SuperChip11 => "schip11",
+            Platform::Modern => "modern",
+            Platform::XoChip => "xochip",
+            Platform::MegaChip => "megachip",
+        }
+    }
+
+    /// Instruction quirks of the interpreter
+    pub fn quirks(&self) -> Quirks {
+        match self {
+            Platform::Cowgod => Quirks::default(),
+            Platform::CosmacVip | Platform::HiRes | Platform::Chip8E | Platform::Chip8X => Quirks {
+                shift_vy: true,
+                memory_increment: MemoryIncrement::XPlusOne,
+                vf_reset: true,
+                jump_vx: false,
+                clip_sprites: true,
+                display_wait: true,
+            },
+            Platform::Chip48 | Platform::SuperChip10 => Quirks {
+                shift_vy: false,
+                memory_increment: MemoryIncrement::X,
+                vf_reset: false,
+                jump_vx: true,
+                clip_sprites: true,
+                display_wait: false,
+            },
+            Platform::SuperChip11 | Platform::MegaChip => Quirks {
+                shift_vy: false,
+                memory_increment: MemoryIncrement::None,
+                vf_reset: false,
+                jump_vx: true,
+                clip_sprites: true,
+                display_wait: false,
+            },
+            Platform::Modern => Quirks {
+                shift_vy: true,
+                memory_increment: MemoryIncrement::XPlusOne,
+                vf_reset: false,
+                jump_vx: false,
+                clip_sprites: true,
+                display_wait: false,
+            },
+            Platform::XoChip => Quirks {
+                shift_vy: true,
+                memory_increment: MemoryIncrement::XPlusOne,
+                vf_reset: false,
+                jump_vx: false,
+                clip_sprites: false,
+                display_wait: false,
+            },
+        }
+    }
+
+    /// Machine the interpreter runs on, loading programs at its program start
+    pub fn machine_config(&self) -> MachineConfig {
+        let display_height = match self {
+            Platform::HiRes => TWO_PAGE_DISPLAY_HEIGHT,
+            _ => DISPLAY_HEIGHT,
+        };
+
+        MachineConfig {
+            memory_size: self.memory_size(),
+            display_width: DISPLAY_WIDTH,
+            display_height,
+            load_address: self.program_start(),
+            quirks: self.quirks(),
+            instruction_set: self.instruction_set(),
+            font: self.font_set(),
+            ..MachineConfig::default()
+        }
+    }
+
+    /// Size of RAM in bytes
+    pub fn memory_size(&self) -> usize {
+        match self {
+            Platform::XoChip => XO_MEMORY_SIZE,
+            Platform::MegaChip => MEGA_MEMORY_SIZE,
+            _ => MEMORY_SIZE,
+        }
+    }
+
+    /// Address programs are loaded at and start executing from
+    pub fn program_start(&self) -> u16 {
+        match self {
+            Platform::Chip8X => 0x300,
+            _ => PROGRAM_START,
+        }
+    }
+
+    /// Instructions executed per 60 Hz frame
+    pub fn tick_rate(&self) -> u32 {
+        match self {
+            Platform::Cowgod => 10,
+            Platform::CosmacVip | Platform::HiRes | Platform::Chip8E | Platform::Chip8X => 15,
+            Platform::Chip48 | Platform::SuperChip10 | Platform::SuperChip11 => 30,
+            Platform::Modern => 12,
+            Platform::XoChip | Platform::MegaChip => 1000,
+        }
+    }
+
+    /// Instructions decoded by the interpreter
+    pub fn instruction_set(&self) -> InstructionSet {
+        match self {
+            Platform::SuperChip10 | Platform::SuperChip11 => InstructionSet::SuperChip,
+            Platform::XoChip => InstructionSet::XoChip,
+            Platform::MegaChip => InstructionSet::MegaChip,
+            Platform::Chip8X => InstructionSet::Chip8X,
+            Platform::HiRes => InstructionSet::HiRes,
+            Platform::Chip8E => InstructionSet::Chip8E,
+            _ => InstructionSet::Chip8,
+        }
+    }
+
+    /// Hex digit sprites of the interpreter
+    pub fn font_set(&self) -> FontSet {
+        match self {
+            Platform::CosmacVip | Platform::HiRes | Platform::Chip8E | Platform::Chip8X => {
+                FontSet::Vip
+            }
+            _ => FontSet::Chip48,
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Platform {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Platform, String> {
+        Platform::ALL
+            .into_iter()
+            .find(|platform| platform.name() == name)
+            .ok_or_else(|| format!("Unknown platform: {}", name))
+    }
+}
@@ -0,0 +1,41 @@
+/// How FX55 and FX65 modify I after storing or loading registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryIncrement {
+    /// I is left unchanged
+    None,
+    /// I is incremented by X, as on CHIP-48
+    X,
+    /// I is incremented by X + 1, as on the COSMAC VIP
+    XPlusOne,
+}
+
+/// Behaviour of instructions that differ between CHIP-8 interpreters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    /// 8XY6 and 8XYE shift VY and store the result in VX, instead of shifting VX in place
+    pub shift_vy: bool,
+    /// Change of I after FX55 and FX65
+    pub memory_increment: MemoryIncrement,
+    /// 8XY1, 8XY2 and 8XY3 reset VF to 0
+    pub vf_reset: bool,
+    /// BXNN jumps to XNN plus VX, instead of BNNN jumping to NNN plus V0
+    pub jump_vx: bool,
+    /// Sprites are clipped at the edges of the display, instead of wrapping around
+    pub clip_sprites: bool,
+    /// DXYN waits for the next display refresh, limiting drawing to 60 sprites per second
+    pub display_wait: bool,
+}
+
+impl Default for Quirks {
+    /// Behaviour described by Cowgod's Chip-8 Technical Reference
+    fn default() -> Quirks {
+        Quirks {
+            shift_vy: false,
+            memory_increment: MemoryIncrement::None,
+            vf_reset: false,
+            jump_vx: false,
+            clip_sprites: false,
+            display_wait: false,
+        }
+    }
+}
@@ -1,18 +1,28 @@
-use super::constants;
+use super::constants::{AUDIO_PATTERN_SIZE, DEFAULT_PITCH};
+use super::display::{ColorDisplay, ColorZones, Display};
+use std::sync::Arc;
 
 pub struct State {
     pub draw_flag: bool,
-    pub display_buffer: [[u8; constants::DISPLAY_HEIGHT]; constants::DISPLAY_WIDTH],
+    pub display_buffer: Display,
+    /// MegaChip colour display, only copied while in MegaChip mode and the draw flag is set
+    pub color_buffer: Option<ColorDisplay>,
+    /// CHIP-8X colour layer over `display_buffer`, only present for CHIP-8X
+    pub color_zones: Option<ColorZones>,
 }
 
 impl State {
     pub fn new(
         draw_flag: &mut bool,
-        display_buffer: [[u8; constants::DISPLAY_HEIGHT]; constants::DISPLAY_WIDTH],
+        display_buffer: &Display,
+        color_display: Option<&ColorDisplay>,
+        color_zones: Option<&ColorZones>,
     ) -> State {
         let state = State {
             draw_flag: *draw_flag,
-            display_buffer,
+            display_buffer: display_buffer.clone(),
+            color_buffer: color_display.filter(|_| *draw_flag).cloned(),
+            color_zones: color_zones.cloned(),
         };
 
         if *draw_flag {
@@ -22,3 +32,50 @@ impl State {
         state
     }
 }
+
+/// Result of running one 60 Hz frame
+pub struct Frame {
+    pub state: State,
+    /// Buzzer change caused by the timer tick at the end of the frame
+    pub sound_event: Option<SoundEvent>,
+}
+
+/// Change in state of the sound timer buzzer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEvent {
+    /// Sound timer became non-zero, buzzer should start
+    Start,
+    /// Sound timer reached zero, buzzer should stop
+    Stop,
+}
+
+/// XO-CHIP audio pattern buffer and the pitch it is played back at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioPattern {
+    /// 128 one-bit samples, most significant bit of the first byte first
+    pub pattern: [u8; AUDIO_PATTERN_SIZE],
+    pub pitch: u8,
+}
+
+impl AudioPattern {
+    /// Number of pattern bits played per second
+    pub fn playback_rate(&self) -> f32 {
+        4000.0 * 2f32.powf((self.pitch as f32 - DEFAULT_PITCH as f32) / 48.0)
+    }
+
+    /// Sample at the given bit position, wrapping around the end of the pattern
+    pub fn bit(&self, index: usize) -> bool {
+        let index = index % (AUDIO_PATTERN_SIZE * 8);
+        self.pattern[index / 8] & (0x80 >> (index % 8)) != 0
+    }
+}
+
+/// MegaChip digitised sound of unsigned 8-bit samples
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    /// Samples played per second
+    pub rate: u16,
+    pub data: Arc<[u8]>,
+    /// Whether playback restarts at the end instead of stopping
+    pub looping: bool,
+}
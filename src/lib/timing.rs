@@ -0,0 +1,24 @@
+use super::constants::{PIXIE_HEIGHT, VIP_FRAME_LINES, VIP_LINE_CYCLES};
+use super::vip::LINE_BYTES;
+
+/// Machine cycles the VIP interrupt routine takes each frame
+pub const VIP_INTERRUPT_CYCLES: i32 = 60;
+
+/// Machine cycles left to the CHIP-8 interpreter in a 60 Hz VIP frame, after
+/// display DMA and the interrupt routine
+pub const VIP_FRAME_CYCLES: i32 = VIP_LINE_CYCLES * VIP_FRAME_LINES as i32
+    - (PIXIE_HEIGHT * LINE_BYTES) as i32
+    - VIP_INTERRUPT_CYCLES;
+
+/// Instructions per frame presets offered by Octo
+pub const TICK_RATES: [u32; 8] = [7, 15, 20, 30, 100, 200, 500, 1000];
+
+/// How many instructions are run in each 60 Hz frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingMode {
+    /// A fixed number of instructions per frame, whatever they cost
+    InstructionsPerFrame(u32),
+    /// As many instructions as fit in the machine cycles of a COSMAC VIP frame,
+    /// ending the frame early when a sprite draw waits for the display interrupt
+    CycleAccurate,
+}
@@ -0,0 +1,176 @@
+mod pixie;
+
+use super::cdp1802::{Bus, Cdp1802};
+use super::constants::{
+    KEYPAD_SIZE, MEMORY_SIZE, PROGRAM_START, VIP_FRAME_LINES, VIP_INTERPRETER_SIZE, VIP_LINE_CYCLES,
+};
+use super::error::Chip8Error;
+use super::state::State;
+pub use pixie::{Pixie, LINE_BYTES};
+
+/// Memory and I/O devices of the COSMAC VIP
+struct VipBus {
+    ram: Vec<u8>,
+    pixie: Pixie,
+    keypad: [bool; KEYPAD_SIZE],
+    /// Key selected by OUT 2, its state is read from EF3
+    key_latch: u8,
+    /// Scanline the Pixie is currently on
+    line: usize,
+}
+
+impl Bus for VipBus {
+    /// RAM is mirrored over the lower half of the address space, the monitor
+    /// ROM in the upper half is not emulated and reads as 0xFF
+    fn read(&mut self, address: u16) -> u8 {
+        if address & 0x8000 != 0 {
+            return 0xFF;
+        }
+        self.ram.as_mut_slice().read(address)
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        if address & 0x8000 == 0 {
+            self.ram.as_mut_slice().write(address, value);
+        }
+    }
+
+    fn output(&mut self, port: u8, value: u8) {
+        match port {
+            0x1 => self.pixie.disable(),
+            0x2 => self.key_latch = value & 0xF,
+            _ => {}
+        }
+    }
+
+    fn input(&mut self, port: u8) -> u8 {
+        if port == 0x1 {
+            self.pixie.enable();
+        }
+        0
+    }
+
+    fn flag(&self, flag: u8) -> bool {
+        match flag {
+            0x1 => self.pixie.flag(self.line),
+            0x3 => self.keypad[self.key_latch as usize],
+            _ => false,
+        }
+    }
+}
+
+/// RCA COSMAC VIP running a CHIP-8 interpreter in machine code
+///
+/// The interpreter image, such as the original 512-byte VIP interpreter, is
+/// supplied by the user and loaded at 0x000 with the program at 0x200. It is
+/// entered the way the monitor runs RAM, with R0 as the program counter and
+/// R1 pointing at the top page of RAM.
+pub struct Vip {
+    cpu: Cdp1802,
+    bus: VipBus,
+    /// Machine cycles run ahead of or behind the scanlines
+    cycle_debt: i32,
+}
+
+impl Vip {
+    /// Boot the interpreter image with the program in 4 KiB of RAM
+    ///
+    /// Fails if the interpreter does not fit below 0x200 or the program does not fit in RAM
+    pub fn new(interpreter: &[u8], program: &[u8]) -> Result<Vip, Chip8Error> {
+        if interpreter.len() > VIP_INTERPRETER_SIZE {
+            return Err(Chip8Error::RomTooLarge {
+                address: 0x000,
+                size: interpreter.len(),
+                max_size: VIP_INTERPRETER_SIZE,
+            });
+        }
+
+        let program_start = PROGRAM_START as usize;
+        if program_start + program.len() > MEMORY_SIZE {
+            return Err(Chip8Error::RomTooLarge {
+                address: PROGRAM_START,
+                size: program.len(),
+                max_size: MEMORY_SIZE - program_start,
+            });
+        }
+
+        let mut ram = vec![0; MEMORY_SIZE];
+        ram[..interpreter.len()].copy_from_slice(interpreter);
+        ram[program_start..program_start + program.len()].copy_from_slice(program);
+
+        let mut cpu = Cdp1802::new();
+        cpu.r[1] = (MEMORY_SIZE as u16 - 1) & 0xFF00;
+
+        Ok(Vip {
+            cpu,
+            bus: VipBus {
+                ram,
+                pixie: Pixie::new(),
+                keypad: [false; KEYPAD_SIZE],
+                key_latch: 0,
+                line: 0,
+            },
+            cycle_debt: 0,
+        })
+    }
+
+    /// Run the machine for one 60 Hz video frame of 262 scanlines
+    pub fn run_frame(&mut self) -> State {
+        for line in 0..VIP_FRAME_LINES {
+            self.bus.line = line;
+
+            if self.bus.pixie.interrupt(line) {
+                self.cpu.interrupt();
+            }
+
+            // DMA steals eight of the fourteen machine cycles of a displayed line
+            let mut cycles = VIP_LINE_CYCLES;
+            if let Some(row) = self.bus.pixie.dma_row(line) {
+                let mut bytes = [0; LINE_BYTES];
+                for byte in bytes.iter_mut() {
+                    *byte = self.cpu.dma_out(&mut self.bus);
+                }
+                self.bus.pixie.store_row(row, &bytes);
+                cycles -= LINE_BYTES as i32;
+            }
+
+            self.cycle_debt += cycles;
+            while self.cycle_debt > 0 {
+                self.cycle_debt -= self.cpu.step(&mut self.bus) as i32;
+            }
+        }
+
+        self.bus.pixie.state()
+    }
+
+    /// Mark key on the hex keypad as pressed
+    pub fn key_down(&mut self, key: u8) {
+        if let Some(state) = self.bus.keypad.get_mut(key as usize) {
+            *state = true;
+        }
+    }
+
+    /// Mark key on the hex keypad as released
+    pub fn key_up(&mut self, key: u8) {
+        if let Some(state) = self.bus.keypad.get_mut(key as usize) {
+            *state = false;
+        }
+    }
+
+    /// Whether the speaker is sounding, driven by the Q output
+    pub fn is_sound_playing(&self) -> bool {
+        self.cpu.q
+    }
+
+    pub fn cpu(&self) -> &Cdp1802 {
+        &self.cpu
+    }
+
+    pub fn memory(&self) -> &[u8] {
+        &self.bus.ram
+    }
+
+    pub fn pixie(&self) -> &Pixie {
+        &self.bus.pixie
+    }
+}
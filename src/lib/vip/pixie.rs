@@ -0,0 +1,88 @@
+use crate::constants::{
+    PIXIE_FIRST_LINE, PIXIE_FLAG_LINES, PIXIE_HEIGHT, PIXIE_INTERRUPT_LINES, PIXIE_WIDTH,
+};
+use crate::display::{row_from_bytes, Display};
+use crate::state::State;
+
+/// Bytes fetched by DMA for each displayed line
+pub const LINE_BYTES: usize = PIXIE_WIDTH / 8;
+
+/// RCA CDP1861 "Pixie" video display controller
+///
+/// Each frame it interrupts the CPU shortly before the display area, then
+/// fetches eight bytes by DMA for each of its 128 lines. EF1 is asserted in
+/// the last four lines before the display area starts and ends.
+pub struct Pixie {
+    enabled: bool,
+    display: Display,
+    draw_flag: bool,
+}
+
+impl Pixie {
+    pub fn new() -> Pixie {
+        Pixie {
+            enabled: false,
+            display: Display::new(PIXIE_WIDTH, PIXIE_HEIGHT),
+            draw_flag: false,
+        }
+    }
+
+    /// Start display DMA and interrupts, triggered by INP 1
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    /// Stop display DMA and interrupts, blanking the screen, triggered by OUT 1
+    pub fn disable(&mut self) {
+        if self.enabled {
+            self.enabled = false;
+            self.display.clear(0x1);
+            self.draw_flag = true;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Whether the interrupt request is raised at the start of the scanline
+    pub fn interrupt(&self, line: usize) -> bool {
+        self.enabled && line == PIXIE_FIRST_LINE - PIXIE_INTERRUPT_LINES
+    }
+
+    /// State of EF1 during the scanline
+    pub fn flag(&self, line: usize) -> bool {
+        let last_line = PIXIE_FIRST_LINE + PIXIE_HEIGHT;
+        self.enabled
+            && ((PIXIE_FIRST_LINE - PIXIE_FLAG_LINES..PIXIE_FIRST_LINE).contains(&line)
+                || (last_line - PIXIE_FLAG_LINES..last_line).contains(&line))
+    }
+
+    /// Display row fetched by DMA during the scanline, if any
+    pub fn dma_row(&self, line: usize) -> Option<usize> {
+        let row = line.checked_sub(PIXIE_FIRST_LINE)?;
+        (self.enabled && row < PIXIE_HEIGHT).then_some(row)
+    }
+
+    /// Show the bytes fetched by DMA on a display row
+    pub fn store_row(&mut self, row: usize, bytes: &[u8; LINE_BYTES]) {
+        if self.display.set_row(0, row, row_from_bytes(bytes)) {
+            self.draw_flag = true;
+        }
+    }
+
+    pub fn display(&self) -> &Display {
+        &self.display
+    }
+
+    /// Snapshot of the display for the frontend, resetting the draw flag
+    pub fn state(&mut self) -> State {
+        State::new(&mut self.draw_flag, &self.display, None, None)
+    }
+}
+
+impl Default for Pixie {
+    fn default() -> Pixie {
+        Pixie::new()
+    }
+}
@@ -0,0 +1,250 @@
+use chip8::cdp1802::{Bus, Cdp1802};
+
+/// RAM with latched outputs and a settable EF3
+struct TestBus {
+    ram: Vec<u8>,
+    outputs: Vec<(u8, u8)>,
+    ef3: bool,
+}
+
+impl TestBus {
+    fn new(program: &[u8]) -> TestBus {
+        let mut ram = vec![0; 0x100];
+        ram[..program.len()].copy_from_slice(program);
+        TestBus {
+            ram,
+            outputs: Vec::new(),
+            ef3: false,
+        }
+    }
+}
+
+impl Bus for TestBus {
+    fn read(&mut self, address: u16) -> u8 {
+        self.ram[address as usize]
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        self.ram[address as usize] = value;
+    }
+
+    fn output(&mut self, port: u8, value: u8) {
+        self.outputs.push((port, value));
+    }
+
+    fn input(&mut self, port: u8) -> u8 {
+        0x40 | port
+    }
+
+    fn flag(&self, flag: u8) -> bool {
+        flag == 3 && self.ef3
+    }
+}
+
+#[test]
+fn test_reset_state() {
+    let cpu = Cdp1802::new();
+
+    assert!(cpu.ie);
+    assert_eq!(cpu.p, 0);
+    assert_eq!(cpu.x, 0);
+    assert_eq!(cpu.r[0], 0);
+}
+
+#[test]
+fn test_immediate_arithmetic() {
+    // 0x00: LDI 0x10
+    // 0x02: ADI 0xF5
+    // 0x04: SMI 0x06
+    // 0x06: SDI 0x01
+    const PROGRAM: [u8; 8] = [0xF8, 0x10, 0xFC, 0xF5, 0xFF, 0x06, 0xFD, 0x01];
+
+    let mut bus = TestBus::new(&PROGRAM);
+    let mut cpu = Cdp1802::new();
+
+    assert_eq!(cpu.step(&mut bus), 2);
+    cpu.step(&mut bus);
+
+    assert_eq!(cpu.d, 0x05);
+    assert!(cpu.df);
+
+    cpu.step(&mut bus);
+
+    assert_eq!(cpu.d, 0xFF);
+    assert!(!cpu.df);
+
+    cpu.step(&mut bus);
+
+    assert_eq!(cpu.d, 0x02);
+    assert!(!cpu.df);
+    assert_eq!(cpu.r[0], 0x08);
+}
+
+#[test]
+fn test_register_operations() {
+    // 0x00: LDI 0x12
+    // 0x02: PHI R3
+    // 0x03: LDI 0x34
+    // 0x05: PLO R3
+    // 0x06: INC R3
+    // 0x07: GHI R3
+    // 0x08: SHR
+    const PROGRAM: [u8; 9] = [0xF8, 0x12, 0xB3, 0xF8, 0x34, 0xA3, 0x13, 0x93, 0xF6];
+
+    let mut bus = TestBus::new(&PROGRAM);
+    let mut cpu = Cdp1802::new();
+
+    for _ in 0..7 {
+        cpu.step(&mut bus);
+    }
+
+    assert_eq!(cpu.r[3], 0x1235);
+    assert_eq!(cpu.d, 0x09);
+    assert!(!cpu.df);
+}
+
+#[test]
+fn test_branches() {
+    // 0x00: LDI 0x00
+    // 0x02: BZ 0x06
+    // 0x04: IDL
+    // 0x06: BNZ 0x04
+    // 0x08: LBR 0x0080
+    const PROGRAM: [u8; 11] = [
+        0xF8, 0x00, 0x32, 0x06, 0x00, 0x00, 0x3A, 0x04, 0xC0, 0x00, 0x80,
+    ];
+
+    let mut bus = TestBus::new(&PROGRAM);
+    let mut cpu = Cdp1802::new();
+
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+
+    assert_eq!(cpu.r[0], 0x06);
+
+    cpu.step(&mut bus);
+
+    assert_eq!(cpu.r[0], 0x08);
+    assert_eq!(cpu.step(&mut bus), 3);
+    assert_eq!(cpu.r[0], 0x80);
+}
+
+#[test]
+fn test_long_skip() {
+    // 0x00: SEQ
+    // 0x01: LSQ
+    // 0x02: (skipped)
+    // 0x04: LSNQ
+    const PROGRAM: [u8; 5] = [0x7B, 0xCD, 0x00, 0x00, 0xC5];
+
+    let mut bus = TestBus::new(&PROGRAM);
+    let mut cpu = Cdp1802::new();
+
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+
+    assert!(cpu.q);
+    assert_eq!(cpu.r[0], 0x04);
+
+    cpu.step(&mut bus);
+
+    assert_eq!(cpu.r[0], 0x05);
+}
+
+#[test]
+fn test_interrupt_and_return() {
+    // 0x10: DEC R2
+    // 0x11: SAV
+    // 0x12: RET
+    let mut program = [0; 0x13];
+    program[0x10..].copy_from_slice(&[0x22, 0x78, 0x70]);
+
+    let mut bus = TestBus::new(&program);
+    let mut cpu = Cdp1802::new();
+    cpu.r[1] = 0x10;
+    cpu.r[2] = 0x40;
+    cpu.p = 3;
+    cpu.x = 5;
+
+    cpu.interrupt();
+
+    assert_eq!((cpu.p, cpu.x, cpu.t), (1, 2, 0x53));
+    assert!(!cpu.ie);
+
+    cpu.interrupt();
+
+    assert_eq!(cpu.p, 1);
+
+    for _ in 0..3 {
+        cpu.step(&mut bus);
+    }
+
+    assert_eq!((cpu.p, cpu.x), (3, 5));
+    assert_eq!(cpu.r[2], 0x40);
+    assert!(cpu.ie);
+}
+
+#[test]
+fn test_mark() {
+    // 0x00: MARK
+    const PROGRAM: [u8; 1] = [0x79];
+
+    let mut bus = TestBus::new(&PROGRAM);
+    let mut cpu = Cdp1802::new();
+    cpu.x = 4;
+    cpu.r[2] = 0x40;
+
+    cpu.step(&mut bus);
+
+    assert_eq!(bus.ram[0x40], 0x40);
+    assert_eq!(cpu.x, 0);
+    assert_eq!(cpu.r[2], 0x3F);
+}
+
+#[test]
+fn test_io_and_flags() {
+    // 0x00: OUT 2
+    // 0x01: (data)
+    // 0x02: SEX R2
+    // 0x03: INP 5
+    // 0x04: B3 0x10
+    // 0x06: BN3 0x20
+    const PROGRAM: [u8; 8] = [0x62, 0x0A, 0xE2, 0x6D, 0x36, 0x10, 0x3E, 0x20];
+
+    let mut bus = TestBus::new(&PROGRAM);
+    let mut cpu = Cdp1802::new();
+    cpu.r[2] = 0x80;
+
+    cpu.step(&mut bus);
+
+    assert_eq!(bus.outputs, [(2, 0x0A)]);
+    assert_eq!(cpu.r[0], 0x02);
+
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+
+    assert_eq!(cpu.d, 0x45);
+    assert_eq!(bus.ram[0x80], 0x45);
+
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+
+    assert_eq!(cpu.r[0], 0x20);
+}
+
+#[test]
+fn test_idle_until_dma() {
+    // 0x00: IDL
+    const PROGRAM: [u8; 2] = [0x00, 0xAB];
+
+    let mut bus = TestBus::new(&PROGRAM);
+    let mut cpu = Cdp1802::new();
+
+    cpu.step(&mut bus);
+
+    assert!(cpu.idle);
+    assert_eq!(cpu.step(&mut bus), 1);
+    assert_eq!(cpu.dma_out(&mut bus), 0xAB);
+    assert!(!cpu.idle);
+    assert_eq!(cpu.r[0], 0x02);
+}
@@ -0,0 +1,179 @@
+use chip8::cpu::Cpu;
+use chip8::display::{ColorZones, DEFAULT_FOREGROUND};
+use chip8::machine::MachineConfig;
+use chip8::platform::Platform;
+use chip8::Chip8;
+
+fn chip8x(rom: &[u8]) -> Cpu {
+    let config = MachineConfig {
+        load_address: 0x200,
+        ..Platform::Chip8X.machine_config()
+    };
+    Cpu::new(rom, config)
+}
+
+#[test]
+fn test_chip8x_program_start() {
+    // 0x300: LD V0 0x01
+    const ROM: [u8; 2] = [0x60, 0x01];
+
+    let mut chip8 = Chip8::new(&ROM, Platform::Chip8X);
+
+    let state = chip8.run_cycle().unwrap();
+
+    assert!(state.color_zones.is_some());
+    assert_eq!(Platform::Chip8X.program_start(), 0x300);
+}
+
+#[test]
+fn test_cycle_bg() {
+    // 0x200: BGCOL
+    const ROM: [u8; 2] = [0x02, 0xA0];
+
+    let mut cpu = chip8x(&ROM);
+
+    assert_eq!(cpu.color_zones.background(), 0);
+
+    let state = cpu.run_cycle().unwrap();
+
+    assert!(state.draw_flag);
+    assert_eq!(state.color_zones.unwrap().background(), 1);
+}
+
+#[test]
+fn test_add_nib_vx_vy() {
+    // 0x200: ADD V0 V1 (nibbles)
+    const ROM: [u8; 2] = [0x50, 0x11];
+
+    let mut cpu = chip8x(&ROM);
+    cpu.gp_reg[0x0] = 0x36;
+    cpu.gp_reg[0x1] = 0x25;
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x0], 0x53);
+}
+
+#[test]
+fn test_col_zone() {
+    // 0x200: COL V0 V2 (zones)
+    const ROM: [u8; 2] = [0xB0, 0x20];
+
+    let mut cpu = chip8x(&ROM);
+    // Columns 1 to 2, zone row 1, colour 4
+    cpu.gp_reg[0x0] = 0x21;
+    cpu.gp_reg[0x1] = 0x11;
+    cpu.gp_reg[0x2] = 0x04;
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.color_zones.foreground(8, 4), 4);
+    assert_eq!(cpu.color_zones.foreground(23, 7), 4);
+    assert_eq!(cpu.color_zones.foreground(7, 4), DEFAULT_FOREGROUND);
+    assert_eq!(cpu.color_zones.foreground(8, 3), DEFAULT_FOREGROUND);
+    assert_eq!(cpu.color_zones.foreground(24, 4), DEFAULT_FOREGROUND);
+    assert_eq!(cpu.color_zones.foreground(8, 8), DEFAULT_FOREGROUND);
+}
+
+#[test]
+fn test_col_area() {
+    // 0x200: COL V0 V2 2
+    const ROM: [u8; 2] = [0xB0, 0x22];
+
+    let mut cpu = chip8x(&ROM);
+    cpu.gp_reg[0x0] = 12;
+    cpu.gp_reg[0x1] = 5;
+    cpu.gp_reg[0x2] = 0x0E;
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.color_zones.foreground(8, 5), 6);
+    assert_eq!(cpu.color_zones.foreground(16, 6), 6);
+    assert_eq!(cpu.color_zones.foreground(8, 7), DEFAULT_FOREGROUND);
+    assert_eq!(cpu.color_zones.foreground(24, 5), DEFAULT_FOREGROUND);
+}
+
+#[test]
+fn test_skp2_sknp2_vx() {
+    // 0x200: SKP2 V0
+    // 0x202: (skipped)
+    // 0x204: SKNP2 V0
+    const ROM: [u8; 6] = [0xE0, 0xF2, 0x00, 0xE0, 0xE0, 0xF5];
+
+    let mut cpu = chip8x(&ROM);
+    cpu.gp_reg[0x0] = 0xA;
+    cpu.key2_down(0xA);
+
+    assert_eq!(cpu.keypad[0xA], 0);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x204);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x206);
+}
+
+#[test]
+fn test_io_ports() {
+    // 0x200: LD V0 0x42
+    // 0x202: OUT V0
+    // 0x204: IN V1
+    const ROM: [u8; 6] = [0x60, 0x42, 0xF0, 0xF8, 0xF1, 0xFB];
+
+    let mut cpu = chip8x(&ROM);
+    cpu.io_input = 0x24;
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.io_output, 0x42);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x1], 0x24);
+}
+
+#[test]
+fn test_bnnn_replaced_by_colour() {
+    // 0x200: COL V0 V0 1
+    const ROM: [u8; 2] = [0xB0, 0x01];
+
+    let mut cpu = chip8x(&ROM);
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x202);
+}
+
+#[test]
+fn test_set_instruction_set_redecodes() {
+    // 0x200: SE V0 V1 on CHIP-8, ADD V0 V1 by nibbles on CHIP-8X
+    const ROM: [u8; 2] = [0x50, 0x11];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x204);
+
+    cpu.pc = 0x200;
+    cpu.set_instruction_set(Platform::Chip8X.instruction_set());
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.instruction_set(), Platform::Chip8X.instruction_set());
+    assert_eq!(cpu.pc, 0x202);
+}
+
+#[test]
+fn test_zones_cover_partial_column() {
+    let mut zones = ColorZones::new(60, 4);
+
+    assert_eq!(zones.foreground(59, 3), DEFAULT_FOREGROUND);
+
+    zones.set_color(7, 2, 0x2);
+
+    assert_eq!(zones.foreground(56, 2), 0x2);
+    assert_eq!(zones.foreground(59, 2), 0x2);
+    assert_eq!(zones.foreground(0, 3), DEFAULT_FOREGROUND);
+    assert_eq!(zones.foreground(59, 3), DEFAULT_FOREGROUND);
+}
@@ -0,0 +1,127 @@
+use chip8::clock::{Clock, RealTimeClock, VirtualClock};
+use chip8::platform::Platform;
+use chip8::state::SoundEvent;
+use chip8::Chip8;
+use std::time::Duration;
+
+const FRAME: Duration = Duration::from_nanos(16_666_667);
+
+/// ROM of ADD V0 0x01 repeated
+fn add_loop() -> Vec<u8> {
+    [0x70, 0x01].repeat(200)
+}
+
+#[test]
+fn test_virtual_clock() {
+    let mut clock = VirtualClock::new();
+
+    assert_eq!(clock.now(), Duration::ZERO);
+
+    clock.advance(FRAME);
+    clock.advance(FRAME);
+
+    assert_eq!(clock.now(), FRAME * 2);
+
+    clock.wait_until(FRAME * 5);
+    clock.wait_until(FRAME);
+
+    assert_eq!(clock.now(), FRAME * 5);
+}
+
+#[test]
+fn test_run_until_runs_due_frames() {
+    let mut clock = VirtualClock::new();
+    let mut chip8 = Chip8::new(&add_loop(), Platform::Cowgod);
+    chip8.set_tick_rate(1);
+
+    clock.advance(FRAME / 2);
+
+    assert!(chip8.run_until(&clock).unwrap().is_none());
+
+    clock.advance(FRAME / 2);
+
+    assert!(chip8.run_until(&clock).unwrap().is_some());
+    assert_eq!(chip8.cpu().gp_reg[0x0], 1);
+
+    clock.advance(FRAME * 3);
+    chip8.run_until(&clock).unwrap();
+
+    assert_eq!(chip8.cpu().gp_reg[0x0], 4);
+}
+
+#[test]
+fn test_run_until_runs_every_frame() {
+    let mut clock = VirtualClock::new();
+    let mut chip8 = Chip8::new(&add_loop(), Platform::Cowgod);
+    chip8.set_tick_rate(1);
+
+    clock.advance(Duration::from_secs(2));
+    chip8.run_until(&clock).unwrap();
+
+    assert_eq!(chip8.cpu().gp_reg[0x0], 120);
+    assert!(clock.now() - chip8.scheduled_time() < FRAME);
+
+    // The same time advanced a frame at a time gives the same run
+    let mut clock = VirtualClock::new();
+    let mut stepped = Chip8::new(&add_loop(), Platform::Cowgod);
+    stepped.set_tick_rate(1);
+    for _ in 0..120 {
+        clock.advance(FRAME);
+        stepped.run_until(&clock).unwrap();
+    }
+
+    assert_eq!(stepped.cpu(), chip8.cpu());
+}
+
+#[test]
+fn test_real_time_clock_skip() {
+    let mut clock = RealTimeClock::new();
+    std::thread::sleep(FRAME * 2);
+
+    clock.skip(clock.now());
+
+    assert!(clock.now() < FRAME * 2);
+}
+
+#[test]
+fn test_run_until_sound_event() {
+    // 0x200: LD V0 0x01
+    // 0x202: LD ST V0
+    // 0x204: JP 0x204
+    const ROM: [u8; 6] = [0x60, 0x01, 0xF0, 0x18, 0x12, 0x04];
+
+    let mut clock = VirtualClock::new();
+    let mut chip8 = Chip8::new(&ROM, Platform::Cowgod);
+
+    clock.advance(FRAME);
+    let frame = chip8.run_until(&clock).unwrap().unwrap();
+
+    assert_eq!(frame.sound_event, Some(SoundEvent::Start));
+
+    clock.advance(FRAME);
+    let frame = chip8.run_until(&clock).unwrap().unwrap();
+
+    assert_eq!(frame.sound_event, Some(SoundEvent::Stop));
+}
+
+#[test]
+fn test_runs_are_reproducible() {
+    // 0x200: RND V0 0xFF
+    // 0x202: LD I 0x300
+    // 0x204: ADD I V0
+    // 0x206: LD [I] V0
+    // 0x208: JP 0x200
+    const ROM: [u8; 10] = [0xC0, 0xFF, 0xA3, 0x00, 0xF0, 0x1E, 0xF0, 0x55, 0x12, 0x00];
+
+    let run = || {
+        let mut clock = VirtualClock::new();
+        let mut chip8 = Chip8::new(&ROM, Platform::Cowgod);
+        for _ in 0..30 {
+            clock.advance(FRAME);
+            chip8.run_until(&clock).unwrap();
+        }
+        chip8.cpu().memory.clone()
+    };
+
+    assert!(run() == run());
+}
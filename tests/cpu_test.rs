@@ -1,4 +1,32 @@
-use chip8::cpu::Cpu;
+use chip8::cpu::{Cpu, StackPolicy};
+use chip8::error::Chip8Error;
+use chip8::machine::MachineConfig;
+use chip8::quirks::{MemoryIncrement, Quirks};
+use chip8::state::SoundEvent;
+
+#[test]
+fn test_cls() {
+    // 0x200: LD I 0x000
+    // 0x202: DRW 0x0 0x0 0x5
+    // 0x204: CLS
+    const ROM: [u8; 6] = [0xA0, 0x00, 0xD0, 0x05, 0x00, 0xE0];
+    const EXPECTED_PC: u16 = 0x206;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_buffer.pixel(0, 0), 1);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, EXPECTED_PC);
+    assert!(cpu
+        .display_buffer
+        .to_pixels()
+        .iter()
+        .all(|&pixel| pixel == 0));
+}
 
 #[test]
 fn test_ret() {
@@ -8,9 +36,9 @@ fn test_ret() {
     const ROM: [u8; 6] = [0x22, 0x04, 0x00, 0x00, 0x00, 0xEE];
     const EXPECTED_PC: u16 = 0x202;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -28,8 +56,8 @@ fn test_jmp() {
     ];
     const EXPECTED_ROM: u16 = 0x20A;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_ROM);
 }
@@ -42,8 +70,8 @@ fn test_call_nnn() {
     const ROM: [u8; 2] = [0x22, 0x04];
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -56,8 +84,8 @@ fn test_se_vk_kk_true() {
     const ROM: [u8; 6] = [0x35, 0x00, 0x00, 0x00, 0x00, 0x00];
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -70,8 +98,8 @@ fn test_se_vk_kk_false() {
     const ROM: [u8; 6] = [0x35, 0x05, 0x00, 0x00, 0x00, 0x00];
     const EXPECTED_PC: u16 = 0x202;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -84,8 +112,8 @@ fn test_sne_vx_nn_true() {
     const ROM: [u8; 6] = [0x45, 0x05, 0x00, 0x00, 0x00, 0x00];
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -98,8 +126,8 @@ fn test_sne_vx_nn_false() {
     const ROM: [u8; 6] = [0x45, 0x00, 0x00, 0x00, 0x00, 0x00];
     const EXPECTED_PC: u16 = 0x202;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -112,8 +140,8 @@ fn test_se_vx_vy_true() {
     const ROM: [u8; 6] = [0x55, 0x05, 0x00, 0x00, 0x00, 0x00];
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -126,9 +154,9 @@ fn test_se_vx_vy_false() {
     const ROM: [u8; 6] = [0x60, 0xAB, 0x50, 0x50, 0x00, 0x00];
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -142,8 +170,8 @@ fn test_ld_vx_kk() {
     const EXPECTED_VAL: u8 = 0x23;
     const EXPECTED_PC: u16 = 0x202;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.pc, EXPECTED_PC);
@@ -159,9 +187,9 @@ fn test_add_vx_kk() {
     const EXPECTED_VAL: u8 = 0x30;
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.pc, EXPECTED_PC);
@@ -177,9 +205,9 @@ fn test_ld_vx_vy() {
     const EXPECTED_VAL: u8 = 0xBC;
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.pc, EXPECTED_PC);
@@ -196,10 +224,10 @@ fn test_or_vx_vy() {
     const EXPECTED_VAL: u8 = 0xFF;
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.pc, EXPECTED_PC);
@@ -216,10 +244,10 @@ fn test_and_vx_vy() {
     const EXPECTED_VAL: u8 = 0x09;
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.pc, EXPECTED_PC);
@@ -236,10 +264,10 @@ fn test_xor_vx_vy() {
     const EXPECTED_VAL: u8 = 0xA6;
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.pc, EXPECTED_PC);
@@ -257,10 +285,10 @@ fn test_add_vx_vy() {
     const EXPECTED_F_VAL: u8 = 0x0;
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.gp_reg[0xF], EXPECTED_F_VAL);
@@ -279,10 +307,10 @@ fn test_add_vx_vy_overflow() {
     const EXPECTED_F_VAL: u8 = 0x1;
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.gp_reg[0xF], EXPECTED_F_VAL);
@@ -301,10 +329,10 @@ fn test_sub_vx_vy() {
     const EXPECTED_F_VAL: u8 = 0x1;
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.gp_reg[0xF], EXPECTED_F_VAL);
@@ -323,10 +351,10 @@ fn test_sub_vx_vy_borrow() {
     const EXPECTED_F_VAL: u8 = 0x0;
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.gp_reg[0xF], EXPECTED_F_VAL);
@@ -344,9 +372,9 @@ fn test_shr_vx_vy() {
     const EXPECTED_F_VAL: u8 = 0x1;
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.gp_reg[0xF], EXPECTED_F_VAL);
@@ -365,10 +393,10 @@ fn test_subn_vx_vy() {
     const EXPECTED_F_VAL: u8 = 0x0;
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.gp_reg[0xF], EXPECTED_F_VAL);
@@ -387,10 +415,10 @@ fn test_subn_vx_vy_borrow() {
     const EXPECTED_F_VAL: u8 = 0x1;
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.gp_reg[0xF], EXPECTED_F_VAL);
@@ -408,9 +436,9 @@ fn test_shl_vx_vy() {
     const EXPECTED_F_VAL: u8 = 0x1;
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.gp_reg[EXPECTED_REG_NUM], EXPECTED_VAL);
     assert_eq!(cpu.gp_reg[0xF], EXPECTED_F_VAL);
@@ -425,9 +453,9 @@ fn test_sne_vx_vy_true() {
     const ROM: [u8; 6] = [0x60, 0xAB, 0x90, 0x50, 0x00, 0x00];
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -440,10 +468,10 @@ fn test_sne_vx_vy_false() {
     const ROM: [u8; 6] = [0x60, 0xAB, 0x65, 0xAB, 0x90, 0x50];
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
@@ -453,16 +481,67 @@ fn test_ld_i_nnn() {
     // 0x200: LD I 0xDAD
     // 0x202: DUMMY INSTRUCTION
     const ROM: [u8; 4] = [0xAD, 0xAD, 0x00, 0x00];
-    const EXPECTED_VAL: u16 = 0xDAD;
+    const EXPECTED_VAL: u32 = 0xDAD;
     const EXPECTED_PC: u16 = 0x202;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.i_reg, EXPECTED_VAL);
     assert_eq!(cpu.pc, EXPECTED_PC);
 }
 
+#[test]
+fn test_jmp_v0_nnn() {
+    // 0x200: LD 0x0 0x04
+    // 0x202: JMP V0 0x300
+    const ROM: [u8; 4] = [0x60, 0x04, 0xB3, 0x00];
+    const EXPECTED_PC: u16 = 0x304;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_rnd_vx_kk() {
+    // 0x200: RND 0x1 0x0F
+    // 0x202: RND 0x2 0x00
+    const ROM: [u8; 4] = [0xC1, 0x0F, 0xC2, 0x00];
+    const EXPECTED_PC: u16 = 0x204;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.gp_reg[0x2] = 0xFF;
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, EXPECTED_PC);
+    assert_eq!(cpu.gp_reg[0x1] & 0xF0, 0);
+    assert_eq!(cpu.gp_reg[0x2], 0);
+}
+
+#[test]
+fn test_rnd_vx_kk_seeded() {
+    // 0x200: RND 0x1 0xFF
+    // 0x202: RND 0x2 0xFF
+    const ROM: [u8; 4] = [0xC1, 0xFF, 0xC2, 0xFF];
+    const SEED: u32 = 0xC0FFEE;
+
+    let mut first = Cpu::new(&ROM, MachineConfig::default());
+    let mut second = Cpu::new(&ROM, MachineConfig::default());
+    first.seed_rng(SEED);
+    second.seed_rng(SEED);
+
+    for _ in 0..2 {
+        first.run_cycle().unwrap();
+        second.run_cycle().unwrap();
+    }
+
+    assert_eq!(first.gp_reg, second.gp_reg);
+}
+
 #[test]
 fn drw_vx_vy_n() {
     // 0x200: LD I 0x006
@@ -471,15 +550,232 @@ fn drw_vx_vy_n() {
     const EXPECTED_PC: u16 = 0x204;
     const EXPECTED_VF: u8 = 0;
 
-    let mut cpu = Cpu::new(&ROM);
-    cpu.run_cycle();
-    cpu.run_cycle();
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
     assert_eq!(cpu.gp_reg[0xf], EXPECTED_VF);
     // TODO: ADD ASSERT FOR DISPLAY_BUFFER
 }
 
+#[test]
+fn test_skp_vx_pressed() {
+    // 0x200: LD 0x3 0xA
+    // 0x202: SKP 0x3
+    const ROM: [u8; 4] = [0x63, 0x0A, 0xE3, 0x9E];
+    const EXPECTED_PC: u16 = 0x206;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.keypad[0xA] = 1;
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_skp_vx_not_pressed() {
+    // 0x200: LD 0x3 0xA
+    // 0x202: SKP 0x3
+    const ROM: [u8; 4] = [0x63, 0x0A, 0xE3, 0x9E];
+    const EXPECTED_PC: u16 = 0x204;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.keypad[0xB] = 1;
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_sknp_vx_pressed() {
+    // 0x200: LD 0x3 0xA
+    // 0x202: SKNP 0x3
+    const ROM: [u8; 4] = [0x63, 0x0A, 0xE3, 0xA1];
+    const EXPECTED_PC: u16 = 0x204;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.keypad[0xA] = 1;
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_sknp_vx_not_pressed() {
+    // 0x200: LD 0x3 0xA
+    // 0x202: SKNP 0x3
+    const ROM: [u8; 4] = [0x63, 0x0A, 0xE3, 0xA1];
+    const EXPECTED_PC: u16 = 0x206;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_ld_vx_dt() {
+    // 0x200: LD 0x4 DT
+    const ROM: [u8; 2] = [0xF4, 0x07];
+    const EXPECTED_VAL: u8 = 0x3C;
+    const EXPECTED_PC: u16 = 0x202;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.delay_timer = EXPECTED_VAL;
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x4], EXPECTED_VAL);
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_ld_vx_k() {
+    // 0x200: LD 0x2 K
+    // 0x202: LD 0x3 0x01
+    const ROM: [u8; 4] = [0xF2, 0x0A, 0x63, 0x01];
+    const EXPECTED_KEY: u8 = 0x7;
+    const EXPECTED_PC: u16 = 0x202;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+
+    assert!(cpu.is_waiting_for_key());
+
+    // Execution is halted while waiting
+    cpu.run_cycle().unwrap();
+    assert_eq!(cpu.pc, EXPECTED_PC);
+
+    // Wait completes on release, not on press
+    cpu.key_down(EXPECTED_KEY);
+    cpu.run_cycle().unwrap();
+    assert!(cpu.is_waiting_for_key());
+    assert_eq!(cpu.pc, EXPECTED_PC);
+
+    cpu.key_up(EXPECTED_KEY);
+
+    assert!(!cpu.is_waiting_for_key());
+    assert_eq!(cpu.gp_reg[0x2], EXPECTED_KEY);
+
+    cpu.run_cycle().unwrap();
+    assert_eq!(cpu.gp_reg[0x3], 0x01);
+}
+
+#[test]
+fn test_ld_vx_k_other_key_released() {
+    // 0x200: LD 0x2 K
+    const ROM: [u8; 2] = [0xF2, 0x0A];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.key_down(0x3);
+    cpu.run_cycle().unwrap();
+    cpu.key_down(0x5);
+    cpu.key_up(0x5);
+
+    // Only release of the first pressed key completes the wait
+    assert!(cpu.is_waiting_for_key());
+
+    cpu.key_up(0x3);
+
+    assert!(!cpu.is_waiting_for_key());
+    assert_eq!(cpu.gp_reg[0x2], 0x3);
+}
+
+#[test]
+fn test_key_down_up() {
+    const ROM: [u8; 2] = [0x00, 0x00];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.key_down(0xC);
+
+    assert_eq!(cpu.keypad[0xC], 1);
+
+    cpu.key_up(0xC);
+
+    assert_eq!(cpu.keypad[0xC], 0);
+
+    // Keys outside the hex keypad are ignored
+    cpu.key_down(0x10);
+    assert!(cpu.keypad.iter().all(|&key| key == 0));
+}
+
+#[test]
+fn test_ld_dt_vx() {
+    // 0x200: LD 0x1 0x2A
+    // 0x202: LD DT 0x1
+    const ROM: [u8; 4] = [0x61, 0x2A, 0xF1, 0x15];
+    const EXPECTED_VAL: u8 = 0x2A;
+    const EXPECTED_PC: u16 = 0x204;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.delay_timer, EXPECTED_VAL);
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_ld_st_vx() {
+    // 0x200: LD 0x1 0x2A
+    // 0x202: LD ST 0x1
+    const ROM: [u8; 4] = [0x61, 0x2A, 0xF1, 0x18];
+    const EXPECTED_VAL: u8 = 0x2A;
+    const EXPECTED_PC: u16 = 0x204;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.sound_timer, EXPECTED_VAL);
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_add_i_vx() {
+    // 0x200: LD I 0x300
+    // 0x202: LD 0x6 0x25
+    // 0x204: ADD I 0x6
+    const ROM: [u8; 6] = [0xA3, 0x00, 0x66, 0x25, 0xF6, 0x1E];
+    const EXPECTED_VAL: u32 = 0x325;
+    const EXPECTED_PC: u16 = 0x206;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.i_reg, EXPECTED_VAL);
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_ld_f_vx() {
+    // 0x200: LD 0x0 0xB
+    // 0x202: LD F 0x0
+    const ROM: [u8; 4] = [0x60, 0x0B, 0xF0, 0x29];
+    const EXPECTED_VAL: u32 = 0xB * 5;
+    const EXPECTED_SPRITE: [u8; 5] = [0xE0, 0x90, 0xE0, 0x90, 0xE0];
+    const EXPECTED_PC: u16 = 0x204;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    let sprite_start = cpu.i_reg as usize;
+
+    assert_eq!(cpu.i_reg, EXPECTED_VAL);
+    assert_eq!(
+        cpu.memory[sprite_start..sprite_start + EXPECTED_SPRITE.len()],
+        EXPECTED_SPRITE
+    );
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
 #[test]
 fn test_ld_i_vx() {
     // 0x200: LD I 0x500
@@ -500,17 +796,17 @@ fn test_ld_i_vx() {
     const EXPECTED_MEM_VALS: [u8; 6] = [0x12, 0x42, 0x10, 0x59, 0x8A, 0x4A];
     const EXPECTED_PC: u16 = 0x210;
 
-    let mut cpu = Cpu::new(&ROM);
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
 
     for _ in 0..NUM_INSTRUCTIONS {
-        cpu.run_cycle();
+        cpu.run_cycle().unwrap();
     }
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 
-    for reg_num in 0..EXPECTED_VX + 1 {
+    for (reg_num, expected_val) in EXPECTED_MEM_VALS.iter().enumerate().take(EXPECTED_VX + 1) {
         let index: usize = (cpu.i_reg as usize) + reg_num;
-        assert_eq!(cpu.memory[index], EXPECTED_MEM_VALS[reg_num]);
+        assert_eq!(cpu.memory[index], *expected_val);
     }
 }
 
@@ -524,15 +820,15 @@ fn test_ld_vx_i() {
     const EXPECTED_REG_VALS: [u8; 4] = [0xA2, 0x00, 0xF3, 0x65];
     const EXPECTED_PC: u16 = 0x204;
 
-    let mut cpu = Cpu::new(&ROM);
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
 
-    cpu.run_cycle();
-    cpu.run_cycle();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 
-    for reg_num in 0..EXPECTED_VX + 1 {
-        assert_eq!(cpu.gp_reg[reg_num], EXPECTED_REG_VALS[reg_num]);
+    for (reg_num, expected_val) in EXPECTED_REG_VALS.iter().enumerate().take(EXPECTED_VX + 1) {
+        assert_eq!(cpu.gp_reg[reg_num], *expected_val);
     }
 }
 
@@ -546,17 +842,17 @@ fn test_ld_b_vx() {
     const EXPECTED_I_VALS: [u8; 3] = [1, 7, 4];
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
 
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 
-    for idx in 0..EXPECTED_I_VALS.len() {
+    for (idx, expected_val) in EXPECTED_I_VALS.iter().enumerate() {
         let i_idx = (cpu.i_reg as usize) + idx;
-        assert_eq!(cpu.memory[i_idx], EXPECTED_I_VALS[idx]);
+        assert_eq!(cpu.memory[i_idx], *expected_val);
     }
 }
 
@@ -570,16 +866,671 @@ fn test_ld_b_vx_2() {
     const EXPECTED_I_VALS: [u8; 3] = [2, 5, 5];
     const EXPECTED_PC: u16 = 0x206;
 
-    let mut cpu = Cpu::new(&ROM);
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
 
-    cpu.run_cycle();
-    cpu.run_cycle();
-    cpu.run_cycle();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
 
     assert_eq!(cpu.pc, EXPECTED_PC);
 
-    for idx in 0..EXPECTED_I_VALS.len() {
+    for (idx, expected_val) in EXPECTED_I_VALS.iter().enumerate() {
         let i_idx = (cpu.i_reg as usize) + idx;
-        assert_eq!(cpu.memory[i_idx], EXPECTED_I_VALS[idx]);
+        assert_eq!(cpu.memory[i_idx], *expected_val);
+    }
+}
+
+#[test]
+fn test_tick_timers() {
+    // 0x200: LD 0x0 0x02
+    // 0x202: LD DT 0x0
+    // 0x204: LD 0x1 DT
+    const ROM: [u8; 6] = [0x60, 0x02, 0xF0, 0x15, 0xF1, 0x07];
+    const EXPECTED_VAL: u8 = 0x01;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    // Timers are only decremented by ticks, not by instructions
+    assert_eq!(cpu.delay_timer, 0x02);
+
+    cpu.tick_timers();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x1], EXPECTED_VAL);
+
+    cpu.tick_timers();
+    cpu.tick_timers();
+
+    assert_eq!(cpu.delay_timer, 0);
+}
+
+#[test]
+fn test_tick_timers_sound_events() {
+    // 0x200: LD 0x0 0x02
+    // 0x202: LD ST 0x0
+    const ROM: [u8; 4] = [0x60, 0x02, 0xF0, 0x18];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+
+    assert_eq!(cpu.tick_timers(), None);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.tick_timers(), Some(SoundEvent::Start));
+    assert!(cpu.is_sound_playing());
+    assert_eq!(cpu.tick_timers(), None);
+    assert_eq!(cpu.tick_timers(), Some(SoundEvent::Stop));
+    assert!(!cpu.is_sound_playing());
+    assert_eq!(cpu.sound_timer, 0);
+}
+
+#[test]
+fn test_unknown_opcode() {
+    // 0x200: LD 0x0 0x00
+    // 0x202: INVALID INSTRUCTION
+    const ROM: [u8; 4] = [0x60, 0x00, 0x80, 0x0F];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::UnknownOpcode {
+            address: 0x202,
+            opcode: 0x800F
+        })
+    );
+}
+
+#[test]
+fn test_pc_out_of_bounds() {
+    // 0x200: JMP 0xFFF
+    const ROM: [u8; 2] = [0x1F, 0xFF];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::PcOutOfBounds { address: 0xFFF })
+    );
+}
+
+#[test]
+fn test_memory_out_of_bounds() {
+    // 0x200: LD I 0xFFE
+    // 0x202: LD I 0x5
+    const ROM: [u8; 4] = [0xAF, 0xFE, 0xF5, 0x55];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::MemoryOutOfBounds {
+            address: 0x202,
+            opcode: 0xF555,
+            memory_address: 0x1000
+        })
+    );
+}
+
+#[test]
+fn test_stack_underflow() {
+    // 0x200: RET
+    const ROM: [u8; 2] = [0x00, 0xEE];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::StackUnderflow {
+            address: 0x200,
+            opcode: 0x00EE
+        })
+    );
+}
+
+#[test]
+fn test_stack_overflow() {
+    // 0x200: CALL 0x200
+    const ROM: [u8; 2] = [0x22, 0x00];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+
+    let result = (0..32).try_for_each(|_| cpu.run_cycle().map(|_| ()));
+
+    assert_eq!(
+        result,
+        Err(Chip8Error::StackOverflow {
+            address: 0x200,
+            opcode: 0x2200
+        })
+    );
+}
+
+#[test]
+fn test_stack_full_depth() {
+    // 0x200: CALL 0x200
+    const ROM: [u8; 2] = [0x22, 0x00];
+    const STACK_DEPTH: usize = 16;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+
+    for _ in 0..STACK_DEPTH {
+        cpu.run_cycle().unwrap();
+    }
+
+    assert_eq!(cpu.call_stack(), [0x202; STACK_DEPTH]);
+    assert!(cpu.run_cycle().is_err());
+}
+
+#[test]
+fn test_call_stack() {
+    // 0x200: CALL 0x204
+    // 0x202: DUMMY INSTRUCTION
+    // 0x204: CALL 0x208
+    // 0x206: DUMMY INSTRUCTION
+    // 0x208: RET
+    const ROM: [u8; 10] = [0x22, 0x04, 0x00, 0x00, 0x22, 0x08, 0x00, 0x00, 0x00, 0xEE];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.call_stack(), [0x202, 0x206]);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.call_stack(), [0x202]);
+    assert_eq!(cpu.pc, 0x206);
+}
+
+#[test]
+fn test_configure_stack_depth() {
+    // 0x200: CALL 0x200
+    const ROM: [u8; 2] = [0x22, 0x00];
+    const STACK_DEPTH: usize = 2;
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.configure_stack(STACK_DEPTH, StackPolicy::Error);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.stack_depth(), STACK_DEPTH);
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::StackOverflow {
+            address: 0x200,
+            opcode: 0x2200
+        })
+    );
+}
+
+#[test]
+fn test_stack_wrap_policy() {
+    // 0x200: CALL 0x204
+    // 0x202: DUMMY INSTRUCTION
+    // 0x204: CALL 0x208
+    // 0x206: DUMMY INSTRUCTION
+    // 0x208: RET
+    const ROM: [u8; 10] = [0x22, 0x04, 0x00, 0x00, 0x22, 0x08, 0x00, 0x00, 0x00, 0xEE];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.configure_stack(1, StackPolicy::Wrap);
+
+    // Second call overwrites the first frame
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.call_stack(), [0x206]);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x206);
+    assert!(cpu.call_stack().is_empty());
+}
+
+#[test]
+fn test_try_new_load_address() {
+    // 0x600: LD 0x0 0x42
+    const ROM: [u8; 2] = [0x60, 0x42];
+    const LOAD_ADDRESS: u16 = 0x600;
+    const EXPECTED_PC: u16 = 0x602;
+
+    let mut cpu = Cpu::try_new(
+        &ROM,
+        MachineConfig {
+            load_address: LOAD_ADDRESS,
+            memory_size: 0x1000,
+            ..MachineConfig::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(cpu.pc, LOAD_ADDRESS);
+    assert_eq!(cpu.memory[0x600..0x602], ROM);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x0], 0x42);
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_try_new_rom_too_large() {
+    const ROM: [u8; 3585] = [0; 3585];
+
+    assert_eq!(
+        Cpu::try_new(
+            &ROM,
+            MachineConfig {
+                load_address: 0x200,
+                memory_size: 0x1000,
+                ..MachineConfig::default()
+            }
+        )
+        .err(),
+        Some(Chip8Error::RomTooLarge {
+            address: 0x200,
+            size: 3585,
+            max_size: 3584
+        })
+    );
+    assert!(Cpu::try_new(
+        &ROM[..3584],
+        MachineConfig {
+            load_address: 0x200,
+            memory_size: 0x1000,
+            ..MachineConfig::default()
+        }
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_try_new_invalid_load_address() {
+    const ROM: [u8; 2] = [0x00, 0xE0];
+
+    assert_eq!(
+        Cpu::try_new(
+            &ROM,
+            MachineConfig {
+                load_address: 0x010,
+                memory_size: 0x1000,
+                ..MachineConfig::default()
+            }
+        )
+        .err(),
+        Some(Chip8Error::InvalidLoadAddress { address: 0x010 })
+    );
+    assert_eq!(
+        Cpu::try_new(
+            &ROM,
+            MachineConfig {
+                load_address: 0x1000,
+                memory_size: 0x1000,
+                ..MachineConfig::default()
+            }
+        )
+        .err(),
+        Some(Chip8Error::InvalidLoadAddress { address: 0x1000 })
+    );
+}
+
+#[test]
+fn test_machine_config_2k_memory() {
+    // 0x200: LD I 0x7FF
+    // 0x202: LD [I] V0
+    const ROM: [u8; 4] = [0xA7, 0xFF, 0xF0, 0x55];
+
+    let config = MachineConfig {
+        memory_size: 0x800,
+        ..MachineConfig::default()
+    };
+    let mut cpu = Cpu::new(&ROM, config);
+    cpu.gp_reg[0x0] = 0x42;
+
+    assert_eq!(cpu.memory.len(), 0x800);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.memory[0x7FF], 0x42);
+    assert_eq!(
+        Cpu::try_new(&[0; 0x601], config).err(),
+        Some(Chip8Error::RomTooLarge {
+            address: 0x200,
+            size: 0x601,
+            max_size: 0x600
+        })
+    );
+}
+
+#[test]
+fn test_machine_config_stack_depth() {
+    // 0x200: CALL 0x200
+    const ROM: [u8; 2] = [0x22, 0x00];
+
+    let config = MachineConfig {
+        stack_depth: 1,
+        ..MachineConfig::default()
+    };
+    let mut cpu = Cpu::new(&ROM, config);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.stack_depth(), 1);
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::StackOverflow {
+            address: 0x200,
+            opcode: 0x2200
+        })
+    );
+}
+
+#[test]
+fn test_machine_config_resolution() {
+    // 0x200: LD I 0x000
+    // 0x202: DRW 0x0 0x0 0x1
+    const ROM: [u8; 4] = [0xA0, 0x00, 0xD0, 0x01];
+
+    let config = MachineConfig {
+        display_width: 128,
+        display_height: 16,
+        ..MachineConfig::default()
+    };
+    let mut cpu = Cpu::new(&ROM, config);
+    cpu.gp_reg[0x0] = 100;
+
+    cpu.run_cycle().unwrap();
+    let state = cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_width(), 128);
+    assert_eq!(state.display_buffer.width(), 128);
+    assert_eq!(state.display_buffer.height(), 16);
+    assert_eq!(state.display_buffer.pixel(100, 4), 1);
+}
+
+#[test]
+fn test_machine_config_resolution_empty() {
+    for (width, height) in [(0, 32), (64, 0)] {
+        let config = MachineConfig {
+            display_width: width,
+            display_height: height,
+            ..MachineConfig::default()
+        };
+
+        assert_eq!(
+            Cpu::try_new(&[], config).err(),
+            Some(Chip8Error::InvalidResolution { width, height })
+        );
+    }
+}
+
+#[test]
+fn test_quirk_shift_vy() {
+    // 0x200: LD 0x2 0x81
+    // 0x202: SHR 0x1 0x2
+    // 0x204: SHL 0x3 0x2
+    const ROM: [u8; 6] = [0x62, 0x81, 0x81, 0x26, 0x83, 0x2E];
+    let quirks = Quirks {
+        shift_vy: true,
+        ..Quirks::default()
+    };
+
+    let mut cpu = Cpu::new(
+        &ROM,
+        MachineConfig {
+            quirks,
+            ..MachineConfig::default()
+        },
+    );
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x1], 0x40);
+    assert_eq!(cpu.gp_reg[0xF], 0x1);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x3], 0x02);
+    assert_eq!(cpu.gp_reg[0x2], 0x81);
+    assert_eq!(cpu.gp_reg[0xF], 0x1);
+}
+
+#[test]
+fn test_quirk_memory_increment() {
+    // 0x200: LD I 0x300
+    // 0x202: LD [I] 0x3
+    // 0x204: LD 0x3 [I]
+    const ROM: [u8; 6] = [0xA3, 0x00, 0xF3, 0x55, 0xF3, 0x65];
+    const EXPECTED_I_VALS: [(MemoryIncrement, u32); 3] = [
+        (MemoryIncrement::None, 0x300),
+        (MemoryIncrement::X, 0x306),
+        (MemoryIncrement::XPlusOne, 0x308),
+    ];
+
+    for (memory_increment, expected_i) in EXPECTED_I_VALS {
+        let quirks = Quirks {
+            memory_increment,
+            ..Quirks::default()
+        };
+
+        let mut cpu = Cpu::new(
+            &ROM,
+            MachineConfig {
+                quirks,
+                ..MachineConfig::default()
+            },
+        );
+        cpu.run_cycle().unwrap();
+        cpu.run_cycle().unwrap();
+        cpu.run_cycle().unwrap();
+
+        assert_eq!(cpu.i_reg, expected_i);
+    }
+}
+
+#[test]
+fn test_quirk_vf_reset() {
+    // 0x200: LD 0xF 0x01
+    // 0x202: OR 0x1 0x2
+    const ROM: [u8; 4] = [0x6F, 0x01, 0x81, 0x21];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0xF], 0x1);
+
+    let quirks = Quirks {
+        vf_reset: true,
+        ..Quirks::default()
+    };
+
+    let mut cpu = Cpu::new(
+        &ROM,
+        MachineConfig {
+            quirks,
+            ..MachineConfig::default()
+        },
+    );
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0xF], 0x0);
+}
+
+#[test]
+fn test_quirk_jump_vx() {
+    // 0x200: LD 0x0 0x01
+    // 0x202: LD 0x3 0x04
+    // 0x204: JMP V3 0x300
+    const ROM: [u8; 6] = [0x60, 0x01, 0x63, 0x04, 0xB3, 0x00];
+    const EXPECTED_PC: u16 = 0x304;
+    let quirks = Quirks {
+        jump_vx: true,
+        ..Quirks::default()
+    };
+
+    let mut cpu = Cpu::new(
+        &ROM,
+        MachineConfig {
+            quirks,
+            ..MachineConfig::default()
+        },
+    );
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, EXPECTED_PC);
+}
+
+#[test]
+fn test_quirk_clip_sprites() {
+    // 0x200: LD 0x0 0x3E
+    // 0x202: LD 0x1 0x1E
+    // 0x204: LD I 0x000
+    // 0x206: DRW 0x0 0x1 0x5
+    const ROM: [u8; 8] = [0x60, 0x3E, 0x61, 0x1E, 0xA0, 0x00, 0xD0, 0x15];
+
+    // Sprite of digit 0 wraps around to the opposite edges
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    for _ in 0..4 {
+        cpu.run_cycle().unwrap();
+    }
+
+    assert_eq!(cpu.display_buffer.pixel(62, 30), 1);
+    assert_eq!(cpu.display_buffer.pixel(0, 30), 1);
+    assert_eq!(cpu.display_buffer.pixel(62, 0), 1);
+
+    let quirks = Quirks {
+        clip_sprites: true,
+        ..Quirks::default()
+    };
+
+    let mut cpu = Cpu::new(
+        &ROM,
+        MachineConfig {
+            quirks,
+            ..MachineConfig::default()
+        },
+    );
+    for _ in 0..4 {
+        cpu.run_cycle().unwrap();
+    }
+
+    assert_eq!(cpu.display_buffer.pixel(62, 30), 1);
+    assert_eq!(cpu.display_buffer.pixel(0, 30), 0);
+    assert_eq!(cpu.display_buffer.pixel(62, 0), 0);
+}
+
+#[test]
+fn test_quirk_display_wait() {
+    // 0x200: DRW 0x0 0x0 0x1
+    // 0x202: LD 0x0 0x01
+    const ROM: [u8; 4] = [0xD0, 0x01, 0x60, 0x01];
+    let quirks = Quirks {
+        display_wait: true,
+        ..Quirks::default()
+    };
+
+    let mut cpu = Cpu::new(
+        &ROM,
+        MachineConfig {
+            quirks,
+            ..MachineConfig::default()
+        },
+    );
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    // Execution is halted until the next display refresh
+    assert_eq!(cpu.gp_reg[0x0], 0x0);
+
+    cpu.tick_timers();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x0], 0x1);
+}
+
+#[test]
+fn test_clone_runs_in_lockstep() {
+    // 0x200: RND V0 0xFF
+    // 0x202: LD I 0x000
+    // 0x204: DRW V0 V0 0x5
+    // 0x206: CALL 0x200
+    const ROM: [u8; 8] = [0xC0, 0xFF, 0xA0, 0x00, 0xD0, 0x05, 0x22, 0x00];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.run_cycle().unwrap();
+
+    let mut copy = cpu.clone();
+    assert_eq!(cpu, copy);
+
+    for _ in 0..7 {
+        cpu.run_cycle().unwrap();
+        copy.run_cycle().unwrap();
+    }
+    assert_eq!(cpu, copy);
+
+    copy.key_down(0x1);
+    assert_ne!(cpu, copy);
+}
+
+#[test]
+fn test_self_modifying_code() {
+    // 0x200: LD V0 0x01
+    // 0x202: ADD V0 0x07
+    // 0x204: LD I 0x201
+    // 0x206: LD [I] V0
+    // 0x208: JP 0x200
+    const ROM: [u8; 10] = [0x60, 0x01, 0x70, 0x07, 0xA2, 0x01, 0xF0, 0x55, 0x12, 0x00];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    for _ in 0..5 {
+        cpu.run_cycle().unwrap();
     }
+
+    // LD V0 0x01 was rewritten to LD V0 0x08 after it ran
+    assert_eq!(cpu.memory[0x201], 0x08);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x0], 0x08);
+}
+
+#[test]
+fn test_self_modifying_bcd() {
+    // 0x200: LD V0 0xE1
+    // 0x202: LD I 0x208
+    // 0x204: LD B V0
+    // 0x206: JP 0x208
+    // 0x208: CLS, overwritten with 0x02 0x02 0x05
+    const ROM: [u8; 10] = [0x60, 0xE1, 0xA2, 0x08, 0xF0, 0x33, 0x12, 0x08, 0x00, 0xE0];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.pc = 0x208;
+    cpu.run_cycle().unwrap();
+    cpu.pc = 0x200;
+
+    for _ in 0..4 {
+        cpu.run_cycle().unwrap();
+    }
+
+    // 0x208 now holds SYS 0x202, which is not a CHIP-8 instruction
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::UnknownOpcode {
+            address: 0x208,
+            opcode: 0x0202
+        })
+    );
 }
@@ -0,0 +1,165 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use chip8::cpu::Cpu;
+use chip8::display::{row_from_bytes, Display, Row, ALL_PLANES, MAX_DISPLAY_WIDTH};
+use chip8::error::Chip8Error;
+use chip8::machine::MachineConfig;
+
+fn hash(display: &Display) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    display.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_draw_row_packs_pixels() {
+    let mut display = Display::new(64, 32);
+
+    assert!(!display.draw_row(4, 2, 0b1010_0001, 8, 0x1, true));
+
+    assert_eq!(display.plane(0)[2], 0b1010_0001 << (MAX_DISPLAY_WIDTH - 12));
+    assert_eq!(display.pixel(4, 2), 1);
+    assert_eq!(display.pixel(5, 2), 0);
+    assert_eq!(display.pixel(11, 2), 1);
+    assert!(display.plane(1).iter().all(|&row| row == 0));
+}
+
+#[test]
+fn test_draw_row_collision() {
+    let mut display = Display::new(64, 32);
+    display.draw_row(0, 0, 0b1100_0000, 8, 0x1, true);
+
+    // Overlapping only in the second plane does not collide
+    assert!(!display.draw_row(0, 0, 0b1000_0000, 8, 0x2, true));
+    assert!(display.draw_row(1, 0, 0b1000_0000, 8, 0x1, true));
+
+    assert_eq!(display.pixel(0, 0), 0b11);
+    assert_eq!(display.pixel(1, 0), 0b00);
+}
+
+#[test]
+fn test_draw_row_wraps_and_clips() {
+    let mut display = Display::new(64, 32);
+
+    display.draw_row(60, 0, 0xFFFF, 16, 0x1, true);
+    display.draw_row(60, 1, 0xFFFF, 16, 0x1, false);
+
+    assert_eq!(display.pixel(63, 0), 1);
+    assert_eq!(display.pixel(0, 0), 1);
+    assert_eq!(display.pixel(11, 0), 1);
+    assert_eq!(display.pixel(12, 0), 0);
+
+    assert_eq!(display.pixel(63, 1), 1);
+    assert_eq!(display.pixel(0, 1), 0);
+
+    // Nothing is left past the display width
+    assert_eq!(display.plane(0)[1] & (Row::MAX >> 64), 0);
+}
+
+#[test]
+fn test_draw_row_full_width() {
+    let mut display = Display::new(128, 64);
+
+    display.draw_row(120, 0, 0xFFFF, 16, 0x1, true);
+
+    assert_eq!(display.pixel(127, 0), 1);
+    assert_eq!(display.pixel(7, 0), 1);
+    assert_eq!(display.pixel(8, 0), 0);
+}
+
+#[test]
+fn test_scroll_rows_and_columns() {
+    let mut display = Display::new(64, 32);
+    display.draw_row(0, 0, 0b1000_0001, 8, 0x1, true);
+    display.draw_row(0, 0, 0b1000_0000, 8, 0x2, true);
+
+    display.scroll_right(4, 0x1);
+    display.scroll_down(2, 0x1);
+
+    assert_eq!(display.pixel(4, 2), 0b01);
+    assert_eq!(display.pixel(11, 2), 0b01);
+    assert_eq!(display.pixel(0, 0), 0b10);
+
+    display.scroll_left(8, ALL_PLANES);
+    display.scroll_up(2, ALL_PLANES);
+
+    assert_eq!(display.pixel(3, 0), 0b01);
+    assert!(display.plane(1).iter().all(|&row| row == 0));
+
+    display.scroll_left(64, 0x1);
+
+    assert!(display.is_clear());
+}
+
+#[test]
+fn test_row_bytes_conversion() {
+    let mut display = Display::new(64, 32);
+
+    let row = row_from_bytes(&[0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    assert!(display.set_row(0, 5, row));
+    assert!(!display.set_row(0, 5, row));
+
+    assert_eq!(display.pixel(0, 5), 1);
+    assert_eq!(display.pixel(63, 5), 1);
+    assert_eq!(
+        display.plane(0)[5].to_be_bytes()[..8],
+        [0x80, 0, 0, 0, 0, 0, 0, 0x01]
+    );
+
+    let pixels = display.to_pixels();
+    assert_eq!(pixels.len(), 64 * 32);
+    assert_eq!(pixels[5 * 64], 1);
+    assert_eq!(pixels[5 * 64 + 63], 1);
+    assert_eq!(pixels.iter().filter(|&&pixel| pixel != 0).count(), 2);
+}
+
+#[test]
+fn test_compare_and_hash() {
+    let mut display = Display::new(64, 32);
+    let mut other = display.clone();
+
+    display.draw_row(10, 10, 0xF0, 8, 0x1, true);
+    assert_ne!(display, other);
+
+    other.draw_row(10, 10, 0b11, 2, 0x1, true);
+    other.draw_row(12, 10, 0b11, 2, 0x1, true);
+
+    assert_eq!(display, other);
+    assert_eq!(hash(&display), hash(&other));
+}
+
+#[test]
+fn test_resolution_too_wide() {
+    let config = MachineConfig {
+        display_width: 256,
+        ..MachineConfig::default()
+    };
+
+    assert_eq!(
+        Cpu::try_new(&[], config).err(),
+        Some(Chip8Error::InvalidResolution {
+            width: 256,
+            height: 32
+        })
+    );
+    assert_eq!(
+        Display::try_new(129, 64).err(),
+        Some(Chip8Error::InvalidResolution {
+            width: 129,
+            height: 64
+        })
+    );
+}
+
+#[test]
+fn test_to_pixels_combines_planes() {
+    let mut display = Display::new(16, 2);
+    display.draw_row(0, 1, 0b1100, 4, 0x1, true);
+    display.draw_row(0, 1, 0b0110, 4, 0x2, true);
+
+    let pixels = display.to_pixels();
+
+    assert_eq!(pixels[..16], [0; 16]);
+    assert_eq!(pixels[16..20], [0b01, 0b11, 0b10, 0b00]);
+}
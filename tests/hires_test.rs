@@ -0,0 +1,159 @@
+use chip8::cpu::Cpu;
+use chip8::platform::Platform;
+use chip8::Chip8;
+
+fn cpu_with(rom: &[u8], platform: Platform) -> Cpu {
+    Cpu::new(rom, platform.machine_config())
+}
+
+#[test]
+fn test_hires_boot_jump() {
+    // 0x200: JP 0x260 (boot jump)
+    let mut rom = vec![0x12, 0x60];
+    rom.resize(0xC0, 0x00);
+    // 0x2C0: LD V0 0x01
+    rom.extend([0x60, 0x01]);
+
+    let mut chip8 = Chip8::new(&rom, Platform::HiRes);
+
+    assert_eq!(chip8.display_height(), 64);
+
+    let state = chip8.run_cycle().unwrap();
+
+    assert_eq!(state.display_buffer.width(), 64);
+    assert_eq!(state.display_buffer.height(), 64);
+}
+
+#[test]
+fn test_hires_without_boot_jump() {
+    // 0x200: LD V0 0x01
+    const ROM: [u8; 2] = [0x60, 0x01];
+
+    let cpu = cpu_with(&ROM, Platform::HiRes);
+
+    assert_eq!(cpu.pc, 0x200);
+    assert_eq!(cpu.display_buffer.height(), 64);
+
+    let cpu = cpu_with(&[0x12, 0x60], Platform::HiRes);
+
+    assert_eq!(cpu.pc, 0x2C0);
+}
+
+#[test]
+fn test_hires_draw_bottom_page() {
+    // 0x200: LD V0 0x30
+    // 0x202: LD I 0x208
+    // 0x204: DRW V0 V0 1
+    // 0x206: CLS (hi-res)
+    // 0x208: sprite
+    const ROM: [u8; 10] = [0x60, 0x30, 0xA2, 0x08, 0xD0, 0x01, 0x02, 0x30, 0x80, 0x00];
+
+    let mut cpu = cpu_with(&ROM, Platform::HiRes);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_buffer.pixel(0x30, 0x30), 1);
+
+    // Wait for the display refresh after drawing
+    cpu.tick_timers();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_buffer.pixel(0x30, 0x30), 0);
+}
+
+#[test]
+fn test_set_display_height() {
+    const ROM: [u8; 2] = [0x00, 0xE0];
+
+    let mut cpu = Cpu::new(&ROM, Platform::Cowgod.machine_config());
+
+    assert_eq!(cpu.display_height(), 32);
+
+    cpu.set_display_height(48);
+
+    assert_eq!(cpu.display_height(), 48);
+    assert_eq!(cpu.display_buffer.height(), 48);
+}
+
+#[test]
+fn test_chip8e_sgt_vx_vy() {
+    // 0x200: SGT V0 V1
+    // 0x202: SGT V1 V0
+    const ROM: [u8; 4] = [0x50, 0x11, 0x51, 0x01];
+
+    let mut cpu = cpu_with(&ROM, Platform::Chip8E);
+    cpu.gp_reg[0x0] = 2;
+    cpu.gp_reg[0x1] = 1;
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x204);
+
+    cpu.pc = 0x202;
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x204);
+}
+
+#[test]
+fn test_chip8e_save_load_vx_vy() {
+    // 0x200: LD I 0x300
+    // 0x202: SAVE V1 - V3
+    // 0x204: LD I 0x300
+    // 0x206: LOAD V4 - V6
+    const ROM: [u8; 8] = [0xA3, 0x00, 0x51, 0x32, 0xA3, 0x00, 0x54, 0x63];
+
+    let mut cpu = cpu_with(&ROM, Platform::Chip8E);
+    cpu.gp_reg[1..4].copy_from_slice(&[0x11, 0x22, 0x33]);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.memory[0x300..0x303], [0x11, 0x22, 0x33]);
+    assert_eq!(cpu.i_reg, 0x303);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[4..7], [0x11, 0x22, 0x33]);
+    assert_eq!(cpu.i_reg, 0x303);
+}
+
+#[test]
+fn test_chip8e_branches() {
+    // 0x200: BF 0x04
+    // 0x206: BB 0x06
+    const ROM: [u8; 8] = [0xBF, 0x04, 0x00, 0x00, 0x00, 0x00, 0xBB, 0x06];
+
+    let mut cpu = cpu_with(&ROM, Platform::Chip8E);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x206);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x202);
+}
+
+#[test]
+fn test_chip8e_stop_and_output() {
+    // 0x200: LD V3 0x42
+    // 0x202: OUT V3
+    // 0x204: STOP
+    const ROM: [u8; 6] = [0x63, 0x42, 0xF3, 0x03, 0x00, 0xED];
+
+    let mut cpu = cpu_with(&ROM, Platform::Chip8E);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.io_output, 0x42);
+
+    cpu.run_cycle().unwrap();
+
+    assert!(cpu.is_halted());
+    assert_eq!(cpu.pc, 0x204);
+}
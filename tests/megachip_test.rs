@@ -0,0 +1,165 @@
+use chip8::cpu::Cpu;
+use chip8::display::BlendMode;
+use chip8::error::Chip8Error;
+use chip8::platform::Platform;
+use chip8::Chip8;
+
+fn mega_chip(rom: &[u8]) -> Cpu {
+    Cpu::new(rom, Platform::MegaChip.machine_config())
+}
+
+#[test]
+fn test_mega_chip_not_decoded_for_super_chip() {
+    // 0x200: MEGAON
+    const ROM: [u8; 2] = [0x00, 0x11];
+
+    let mut cpu = Cpu::new(&ROM, Platform::SuperChip11.machine_config());
+
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::UnknownOpcode {
+            address: 0x200,
+            opcode: 0x0011
+        })
+    );
+}
+
+#[test]
+fn test_mega_on_off() {
+    // 0x200: MEGAON
+    // 0x202: MEGAOFF
+    const ROM: [u8; 4] = [0x00, 0x11, 0x00, 0x10];
+
+    let mut cpu = mega_chip(&ROM);
+
+    let state = cpu.run_cycle().unwrap();
+    let color_buffer = state.color_buffer.unwrap();
+
+    assert_eq!(color_buffer.width(), 256);
+    assert_eq!(color_buffer.height(), 192);
+
+    let state = cpu.run_cycle().unwrap();
+
+    assert!(state.draw_flag);
+    assert!(state.color_buffer.is_none());
+    assert!(cpu.color_display.is_none());
+}
+
+#[test]
+fn test_ld_i_long_24() {
+    // 0x200: LDHI I 0x123456
+    // 0x204: SE V0 0x00
+    // 0x206: LDHI I 0x000000
+    const ROM: [u8; 10] = [0x01, 0x12, 0x34, 0x56, 0x30, 0x00, 0x01, 0x00, 0x00, 0x00];
+
+    let mut cpu = mega_chip(&ROM);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.i_reg, 0x123456);
+    assert_eq!(cpu.pc, 0x204);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x20A);
+}
+
+#[test]
+fn test_ld_pal() {
+    // 0x200: LD I 0x206
+    // 0x202: LDPAL 2
+    // 0x204: JP 0x204
+    // 0x206: two ARGB colours
+    const ROM: [u8; 14] = [
+        0xA2, 0x06, 0x02, 0x02, 0x12, 0x04, 0xFF, 0x10, 0x20, 0x30, 0x80, 0x40, 0x50, 0x60,
+    ];
+
+    let mut cpu = mega_chip(&ROM);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.palette[0], [0, 0, 0, 0]);
+    assert_eq!(cpu.palette[1], [0x10, 0x20, 0x30, 0xFF]);
+    assert_eq!(cpu.palette[2], [0x40, 0x50, 0x60, 0x80]);
+}
+
+#[test]
+fn test_drw_color_sprite() {
+    // 0x200: MEGAON
+    // 0x202: SPRW 2
+    // 0x204: SPRH 1
+    // 0x206: COL 1
+    // 0x208: LD I 0x210
+    // 0x20A: DRW V0 V0 0
+    // 0x20C: DRW V0 V0 0
+    // 0x20E: JP 0x20E
+    // 0x210: sprite of palette indices 1 and 0
+    const ROM: [u8; 18] = [
+        0x00, 0x11, 0x03, 0x02, 0x04, 0x01, 0x09, 0x01, 0xA2, 0x10, 0xD0, 0x00, 0xD0, 0x00, 0x12,
+        0x0E, 0x01, 0x00,
+    ];
+
+    let mut cpu = mega_chip(&ROM);
+    cpu.palette[1] = [0xFF, 0x00, 0x00, 0xFF];
+
+    for _ in 0..6 {
+        cpu.run_cycle().unwrap();
+    }
+
+    let display = cpu.color_display.as_ref().unwrap();
+    assert_eq!(display.index(0, 0), 1);
+    assert_eq!(display.color(0, 0), [0xFF, 0x00, 0x00, 0xFF]);
+    assert_eq!(display.index(1, 0), 0);
+    assert_eq!(cpu.gp_reg[0xF], 0);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0xF], 1);
+}
+
+#[test]
+fn test_blend_modes() {
+    // 0x200: BLEND 2
+    const ROM: [u8; 2] = [0x08, 0x02];
+
+    let mut cpu = mega_chip(&ROM);
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.blend_mode, BlendMode::Alpha50);
+
+    let src = [0xFF, 0x80, 0x00, 0xFF];
+    let dst = [0x00, 0x80, 0xFF, 0xFF];
+    assert_eq!(BlendMode::Normal.blend(src, dst), src);
+    assert_eq!(BlendMode::Alpha50.blend(src, dst), [0x7F, 0x80, 0x7F, 0xFF]);
+    assert_eq!(BlendMode::Add.blend(src, dst), [0xFF, 0xFF, 0xFF, 0xFF]);
+    assert_eq!(
+        BlendMode::Multiply.blend(src, dst),
+        [0x00, 0x40, 0x00, 0xFF]
+    );
+}
+
+#[test]
+fn test_play_stop_sample() {
+    // 0x200: LD I 0x206
+    // 0x202: PLAY 1
+    // 0x204: STOP
+    // 0x206: 8000 Hz sound of 3 samples
+    const ROM: [u8; 15] = [
+        0xA2, 0x06, 0x06, 0x01, 0x07, 0x00, 0x1F, 0x40, 0x00, 0x00, 0x03, 0x00, 0x80, 0xFF, 0x00,
+    ];
+
+    let mut chip8 = Chip8::new(&ROM, Platform::MegaChip);
+
+    chip8.run_cycle().unwrap();
+    chip8.run_cycle().unwrap();
+
+    let sample = chip8.sample().unwrap();
+    assert_eq!(sample.rate, 8000);
+    assert_eq!(*sample.data, [0x80, 0xFF, 0x00]);
+    assert!(!sample.looping);
+
+    chip8.run_cycle().unwrap();
+
+    assert!(chip8.sample().is_none());
+}
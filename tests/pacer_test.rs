@@ -0,0 +1,81 @@
+use chip8::clock::{Clock, VirtualClock};
+use chip8::gui::pacer::FramePacer;
+use std::time::Duration;
+
+const FRAME_RATE: u32 = 200;
+const FRAME: Duration = Duration::from_millis(5);
+
+fn pacer() -> FramePacer<VirtualClock> {
+    FramePacer::with_clock(FRAME_RATE, VirtualClock::new())
+}
+
+#[test]
+fn test_wait_paces_frames() {
+    let mut pacer = pacer();
+
+    for _ in 0..10 {
+        pacer.wait();
+    }
+
+    assert_eq!(pacer.clock().now(), FRAME * 10);
+    assert_eq!(pacer.drift(), Duration::ZERO);
+}
+
+#[test]
+fn test_wait_absorbs_work_within_frame() {
+    let mut pacer = pacer();
+
+    for _ in 0..10 {
+        pacer.clock_mut().advance(FRAME / 2);
+        pacer.wait();
+    }
+
+    // Deadlines advance by whole frames, so the work doesn't add up
+    assert_eq!(pacer.clock().now(), FRAME * 10);
+}
+
+#[test]
+fn test_wait_resyncs_after_falling_behind() {
+    let mut pacer = pacer();
+    pacer.clock_mut().advance(FRAME * 5);
+
+    pacer.wait();
+
+    assert_eq!(pacer.drift(), FRAME * 4);
+
+    // The next frame is a whole frame later instead of immediately
+    pacer.wait();
+
+    assert_eq!(pacer.clock().now(), FRAME * 6);
+}
+
+#[test]
+fn test_fps_counts_missed_frames() {
+    let mut pacer = pacer();
+
+    // Stall for 300 ms within the first one second measurement
+    while pacer.clock().now() < Duration::from_millis(400) {
+        pacer.wait();
+    }
+    pacer.clock_mut().advance(Duration::from_millis(300));
+    while pacer.clock().now() < Duration::from_secs(1) {
+        pacer.wait();
+    }
+
+    // 80 frames before the stall, one late frame and 60 after it
+    assert_eq!(pacer.fps(), 141.0);
+}
+
+#[test]
+fn test_record_frame_measures_external_pacing() {
+    let mut pacer = pacer();
+
+    // Presenting with vsync on a 100 Hz monitor
+    for _ in 0..100 {
+        pacer.clock_mut().advance(Duration::from_millis(10));
+        pacer.record_frame();
+    }
+
+    assert_eq!(pacer.fps(), 100.0);
+    assert_eq!(pacer.clock().now(), Duration::from_secs(1));
+}
@@ -0,0 +1,47 @@
+use chip8::cpu::Cpu;
+use chip8::font::{FontSet, CHIP48_FONT, VIP_FONT};
+use chip8::platform::Platform;
+use chip8::quirks::Quirks;
+
+#[test]
+fn test_cowgod_preserves_default_quirks() {
+    assert_eq!(Platform::default(), Platform::Cowgod);
+    assert_eq!(Platform::Cowgod.quirks(), Quirks::default());
+    assert_eq!(Platform::Cowgod.font_set(), FontSet::Chip48);
+}
+
+#[test]
+fn test_platform_names() {
+    for platform in Platform::ALL {
+        assert_eq!(platform.name().parse::<Platform>(), Ok(platform));
+        assert_eq!(platform.to_string(), platform.name());
+    }
+
+    assert!("chip9".parse::<Platform>().is_err());
+}
+
+#[test]
+fn test_platform_settings() {
+    assert!(Platform::CosmacVip.quirks().display_wait);
+    assert!(Platform::SuperChip11.quirks().jump_vx);
+    assert_eq!(Platform::CosmacVip.font_set(), FontSet::Vip);
+    assert_eq!(Platform::XoChip.memory_size(), 0x10000);
+    assert_eq!(Platform::CosmacVip.memory_size(), 0x1000);
+}
+
+#[test]
+fn test_load_font() {
+    const ROM: [u8; 2] = [0x00, 0xE0];
+
+    let mut cpu = Cpu::new(&ROM, Platform::CosmacVip.machine_config());
+
+    assert_eq!(cpu.memory[0..VIP_FONT.len()], VIP_FONT);
+
+    cpu.load_font(FontSet::Chip48);
+
+    assert_eq!(cpu.memory[0..CHIP48_FONT.len()], CHIP48_FONT);
+
+    let cpu = Cpu::new(&ROM, Platform::Cowgod.machine_config());
+
+    assert_eq!(cpu.memory[0..CHIP48_FONT.len()], CHIP48_FONT);
+}
@@ -0,0 +1,117 @@
+// Translated from a CHIP-8 program by chip8-recompile, do not edit
+//
+// Run with Engine::Native(run) on a CPU of the platform it was translated
+// for, whose quirks and instruction set are built in.
+
+use chip8::cpu::{BlockRun, Cpu};
+use chip8::error::Chip8Error;
+
+/// First address of the translated code
+const CODE_START: usize = 0x200;
+
+/// Program bytes the blocks were translated from
+const CODE: [u8; 10] = [
+    0x60, 0x01, 0x70, 0x07, 0xA2, 0x01, 0xF0, 0x55, 0x12, 0x00,
+];
+
+/// Run the translated block at pc, or a single instruction with the
+/// interpreter where there is none
+pub fn run(cpu: &mut Cpu, max_instructions: u32, max_cycles: u32) -> Result<BlockRun, Chip8Error> {
+    let mut run = BlockRun::default();
+    if !cpu.is_running() {
+        return Ok(run);
+    }
+
+    let limit = BlockRun {
+        instructions: max_instructions,
+        cycles: max_cycles,
+    };
+    let translated = match cpu.pc {
+        0x200 => block_200(cpu, &mut run, limit)?,
+        0x208 => block_208(cpu, &mut run, limit)?,
+        _ => false,
+    };
+
+    // Code outside the blocks, or overwritten since, is left to the interpreter
+    if !translated {
+        step(cpu, &mut run)?;
+    }
+
+    Ok(run)
+}
+
+/// Whether the run used up the instruction or cycle limit
+fn done(run: &BlockRun, limit: BlockRun) -> bool {
+    run.instructions >= limit.instructions || run.cycles >= limit.cycles
+}
+
+/// Whether the code between the addresses is still the translated code
+fn intact(cpu: &Cpu, start: usize, end: usize) -> bool {
+    cpu.memory.get(start..end) == Some(&CODE[start - CODE_START..end - CODE_START])
+}
+
+/// Count an instruction run by translated code
+fn native(run: &mut BlockRun, cycles: u32) {
+    run.instructions += 1;
+    run.cycles += cycles;
+}
+
+/// Run the instruction at pc with the interpreter
+fn step(cpu: &mut Cpu, run: &mut BlockRun) -> Result<(), Chip8Error> {
+    let cycles = cpu.step()?;
+    run.instructions += (cycles > 0) as u32;
+    run.cycles += cycles;
+    Ok(())
+}
+
+fn block_200(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x200, 0x208) {
+        return Ok(false);
+    }
+
+    // 0x200: 6001
+    if done(run, limit) {
+        return Ok(true);
+    }
+    cpu.gp_reg[0x0] = 0x01;
+    cpu.pc = 0x202;
+    native(run, 46);
+
+    // 0x202: 7007
+    if done(run, limit) {
+        return Ok(true);
+    }
+    cpu.gp_reg[0x0] = cpu.gp_reg[0x0].wrapping_add(0x07);
+    cpu.pc = 0x204;
+    native(run, 50);
+
+    // 0x204: A201
+    if done(run, limit) {
+        return Ok(true);
+    }
+    cpu.i_reg = 0x201;
+    cpu.pc = 0x206;
+    native(run, 52);
+
+    // 0x206: F055
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
+
+fn block_208(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x208, 0x20A) {
+        return Ok(false);
+    }
+
+    // 0x208: 1200
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
@@ -0,0 +1,244 @@
+// Translated from a CHIP-8 program by chip8-recompile, do not edit
+//
+// Run with Engine::Native(run) on a CPU of the platform it was translated
+// for, whose quirks and instruction set are built in.
+
+use chip8::cpu::{BlockRun, Cpu};
+use chip8::error::Chip8Error;
+
+/// First address of the translated code
+const CODE_START: usize = 0x200;
+
+/// Program bytes the blocks were translated from
+const CODE: [u8; 28] = [
+    0x60, 0x05, 0xC1, 0xFF, 0x80, 0x14, 0x82, 0x06, 0xF0, 0x29, 0xD1, 0x25, 0x22, 0x14, 0x30, 0x00,
+    0x12, 0x02, 0x12, 0x00, 0xA3, 0x00, 0xF1, 0x33, 0xF2, 0x65, 0x00, 0xEE,
+];
+
+/// Run the translated block at pc, or a single instruction with the
+/// interpreter where there is none
+pub fn run(cpu: &mut Cpu, max_instructions: u32, max_cycles: u32) -> Result<BlockRun, Chip8Error> {
+    let mut run = BlockRun::default();
+    if !cpu.is_running() {
+        return Ok(run);
+    }
+
+    let limit = BlockRun {
+        instructions: max_instructions,
+        cycles: max_cycles,
+    };
+    let translated = match cpu.pc {
+        0x200 => block_200(cpu, &mut run, limit)?,
+        0x202 => block_202(cpu, &mut run, limit)?,
+        0x20C => block_20c(cpu, &mut run, limit)?,
+        0x20E => block_20e(cpu, &mut run, limit)?,
+        0x210 => block_210(cpu, &mut run, limit)?,
+        0x212 => block_212(cpu, &mut run, limit)?,
+        0x214 => block_214(cpu, &mut run, limit)?,
+        0x218 => block_218(cpu, &mut run, limit)?,
+        _ => false,
+    };
+
+    // Code outside the blocks, or overwritten since, is left to the interpreter
+    if !translated {
+        step(cpu, &mut run)?;
+    }
+
+    Ok(run)
+}
+
+/// Whether the run used up the instruction or cycle limit
+fn done(run: &BlockRun, limit: BlockRun) -> bool {
+    run.instructions >= limit.instructions || run.cycles >= limit.cycles
+}
+
+/// Whether the code between the addresses is still the translated code
+fn intact(cpu: &Cpu, start: usize, end: usize) -> bool {
+    cpu.memory.get(start..end) == Some(&CODE[start - CODE_START..end - CODE_START])
+}
+
+/// Run the instruction at pc with the interpreter
+fn step(cpu: &mut Cpu, run: &mut BlockRun) -> Result<(), Chip8Error> {
+    let cycles = cpu.step()?;
+    run.instructions += (cycles > 0) as u32;
+    run.cycles += cycles;
+    Ok(())
+}
+
+fn block_200(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x200, 0x20C) {
+        return Ok(false);
+    }
+
+    // 0x200: 6005
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x202: C1FF
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x204: 8014
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x206: 8206
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x208: F029
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x20A: D125
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
+
+fn block_202(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x202, 0x20C) {
+        return Ok(false);
+    }
+
+    // 0x202: C1FF
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x204: 8014
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x206: 8206
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x208: F029
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x20A: D125
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
+
+fn block_20c(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x20C, 0x20E) {
+        return Ok(false);
+    }
+
+    // 0x20C: 2214
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
+
+fn block_20e(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x20E, 0x210) {
+        return Ok(false);
+    }
+
+    // 0x20E: 3000
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
+
+fn block_210(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x210, 0x212) {
+        return Ok(false);
+    }
+
+    // 0x210: 1202
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
+
+fn block_212(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x212, 0x214) {
+        return Ok(false);
+    }
+
+    // 0x212: 1200
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
+
+fn block_214(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x214, 0x218) {
+        return Ok(false);
+    }
+
+    // 0x214: A300
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x216: F133
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
+
+fn block_218(cpu: &mut Cpu, run: &mut BlockRun, limit: BlockRun) -> Result<bool, Chip8Error> {
+    if !intact(cpu, 0x218, 0x21C) {
+        return Ok(false);
+    }
+
+    // 0x218: F265
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    // 0x21A: 00EE
+    if done(run, limit) {
+        return Ok(true);
+    }
+    step(cpu, run)?;
+
+    Ok(true)
+}
@@ -0,0 +1,193 @@
+use chip8::cpu::{Cpu, Engine, NativeProgram};
+use chip8::machine::{MachineConfig, MemoryLayout};
+use chip8::platform::Platform;
+use chip8::timing::TimingMode;
+use chip8::Chip8;
+
+// Modules generated by chip8-recompile from the ROMs below for the default platform
+mod mixed {
+    include!("recompiled/mixed.rs");
+}
+mod self_modifying {
+    include!("recompiled/self_modifying.rs");
+}
+mod computed_jump {
+    include!("recompiled/computed_jump.rs");
+}
+// Generated from the mixed ROM with the VIP memory layout
+mod vip_layout {
+    include!("recompiled/vip_layout.rs");
+}
+
+// 0x200: LD V0 0x05
+// 0x202: RND V1 0xFF
+// 0x204: ADD V0 V1
+// 0x206: SHR V2 V0
+// 0x208: LD F V0
+// 0x20A: DRW V1 V2 0x5
+// 0x20C: CALL 0x214
+// 0x20E: SE V0 0x00
+// 0x210: JP 0x202
+// 0x212: JP 0x200
+// 0x214: LD I 0x300
+// 0x216: LD B V1
+// 0x218: LD V2 [I]
+// 0x21A: RET
+const MIXED_ROM: [u8; 28] = [
+    0x60, 0x05, 0xC1, 0xFF, 0x80, 0x14, 0x82, 0x06, 0xF0, 0x29, 0xD1, 0x25, 0x22, 0x14, 0x30, 0x00,
+    0x12, 0x02, 0x12, 0x00, 0xA3, 0x00, 0xF1, 0x33, 0xF2, 0x65, 0x00, 0xEE,
+];
+
+// 0x200: LD V0 0x01
+// 0x202: ADD V0 0x07
+// 0x204: LD I 0x201
+// 0x206: LD [I] V0
+// 0x208: JP 0x200
+const SELF_MODIFYING_ROM: [u8; 10] = [0x60, 0x01, 0x70, 0x07, 0xA2, 0x01, 0xF0, 0x55, 0x12, 0x00];
+
+// 0x200: LD V0 0x04
+// 0x202: JP V0 0x204
+// 0x204: LD V1 0x01
+// 0x206: JP 0x206
+// 0x208: LD V2 0x02
+// 0x20A: JP 0x20A
+const COMPUTED_JUMP_ROM: [u8; 12] = [
+    0x60, 0x04, 0xB2, 0x04, 0x61, 0x01, 0x12, 0x06, 0x62, 0x02, 0x12, 0x0A,
+];
+
+fn vip_config() -> MachineConfig {
+    MachineConfig {
+        memory_layout: MemoryLayout::Vip,
+        ..MachineConfig::default()
+    }
+}
+
+/// Run the translated program on one CPU and as many interpreter steps on a
+/// copy, checking both agree after every call
+fn assert_lockstep(rom: &[u8], config: MachineConfig, program: NativeProgram, calls: usize) -> Cpu {
+    let mut native = Cpu::new(rom, config);
+    let mut interpreted = native.clone();
+
+    for _ in 0..calls {
+        let run = program(&mut native, u32::MAX, u32::MAX).unwrap();
+
+        let mut cycles = 0;
+        for _ in 0..run.instructions {
+            cycles += interpreted.step().unwrap();
+        }
+
+        assert_eq!(run.cycles, cycles);
+        assert_eq!(native, interpreted);
+    }
+
+    native
+}
+
+#[test]
+fn test_recompile_matches_generated_modules() {
+    let cases: [(&[u8], &str); 3] = [
+        (&MIXED_ROM, include_str!("recompiled/mixed.rs")),
+        (
+            &SELF_MODIFYING_ROM,
+            include_str!("recompiled/self_modifying.rs"),
+        ),
+        (
+            &COMPUTED_JUMP_ROM,
+            include_str!("recompiled/computed_jump.rs"),
+        ),
+    ];
+
+    for (rom, source) in cases {
+        let chip8 = Chip8::new(rom, Platform::Cowgod);
+        assert_eq!(chip8.cpu().recompile(), source);
+    }
+
+    let cpu = Cpu::new(&MIXED_ROM, vip_config());
+    assert_eq!(cpu.recompile(), include_str!("recompiled/vip_layout.rs"));
+}
+
+#[test]
+fn test_lockstep_mixed() {
+    assert_lockstep(&MIXED_ROM, MachineConfig::default(), mixed::run, 200);
+}
+
+#[test]
+fn test_lockstep_self_modifying_code() {
+    let cpu = assert_lockstep(
+        &SELF_MODIFYING_ROM,
+        MachineConfig::default(),
+        self_modifying::run,
+        8,
+    );
+
+    // The overwritten block at 0x200 was left to the interpreter, which ran
+    // LD V0 0x08 and later LD V0 0x0F
+    assert_eq!(cpu.pc, 0x202);
+    assert_eq!(cpu.gp_reg[0x0], 0x0F);
+}
+
+#[test]
+fn test_computed_jump_falls_back_to_interpreter() {
+    let cpu = assert_lockstep(
+        &COMPUTED_JUMP_ROM,
+        MachineConfig::default(),
+        computed_jump::run,
+        4,
+    );
+
+    assert_eq!(cpu.pc, 0x20A);
+    assert_eq!(cpu.gp_reg[0x1], 0x00);
+    assert_eq!(cpu.gp_reg[0x2], 0x02);
+}
+
+#[test]
+fn test_lockstep_vip_layout() {
+    let source = include_str!("recompiled/vip_layout.rs");
+
+    // Registers mirrored in VIP memory are only synced by the interpreter
+    assert!(!source.contains("native("));
+
+    assert_lockstep(&MIXED_ROM, vip_config(), vip_layout::run, 200);
+
+    // Stopping after every instruction leaves no later step to sync memory
+    let mut native = Cpu::new(&MIXED_ROM, vip_config());
+    let mut interpreted = native.clone();
+    for _ in 0..200 {
+        vip_layout::run(&mut native, 1, u32::MAX).unwrap();
+        interpreted.step().unwrap();
+
+        assert_eq!(native, interpreted);
+    }
+}
+
+#[test]
+fn test_instruction_limit() {
+    let mut cpu = Cpu::new(&MIXED_ROM, MachineConfig::default());
+
+    let run = mixed::run(&mut cpu, 3, u32::MAX).unwrap();
+
+    assert_eq!(run.instructions, 3);
+    assert_eq!(cpu.pc, 0x206);
+}
+
+#[test]
+fn test_native_engine_runs_same_frames() {
+    for timing in [
+        TimingMode::InstructionsPerFrame(15),
+        TimingMode::CycleAccurate,
+    ] {
+        let mut interpreted = Chip8::new(&MIXED_ROM, Platform::Cowgod);
+        interpreted.set_timing(timing);
+        let mut native = Chip8::new(&MIXED_ROM, Platform::Cowgod);
+        native.set_timing(timing);
+        native.set_engine(Engine::Native(mixed::run));
+
+        for _ in 0..60 {
+            let expected = interpreted.run_frame().unwrap();
+            let frame = native.run_frame().unwrap();
+
+            assert_eq!(frame.sound_event, expected.sound_event);
+            assert_eq!(native.cpu(), interpreted.cpu());
+        }
+    }
+}
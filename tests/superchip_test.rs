@@ -0,0 +1,190 @@
+use chip8::cpu::Cpu;
+use chip8::error::Chip8Error;
+use chip8::font::{BIG_FONT, BIG_FONT_ADDRESS};
+use chip8::platform::Platform;
+
+fn super_chip(rom: &[u8]) -> Cpu {
+    Cpu::new(rom, Platform::SuperChip11.machine_config())
+}
+
+#[test]
+fn test_super_chip_not_decoded_for_chip8() {
+    // 0x200: HIGH
+    const ROM: [u8; 2] = [0x00, 0xFF];
+
+    let mut cpu = Cpu::new(&ROM, Platform::Chip48.machine_config());
+
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::UnknownOpcode {
+            address: 0x200,
+            opcode: 0x00FF
+        })
+    );
+}
+
+#[test]
+fn test_high_low() {
+    // 0x200: HIGH
+    // 0x202: LOW
+    const ROM: [u8; 4] = [0x00, 0xFF, 0x00, 0xFE];
+
+    let mut cpu = super_chip(&ROM);
+
+    assert_eq!(cpu.display_buffer.width(), 64);
+
+    let state = cpu.run_cycle().unwrap();
+
+    assert!(state.draw_flag);
+    assert_eq!(state.display_buffer.width(), 128);
+    assert_eq!(state.display_buffer.height(), 64);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_buffer.width(), 64);
+    assert_eq!(cpu.display_buffer.height(), 32);
+}
+
+#[test]
+fn test_drw_vx_vy_0() {
+    // 0x200: HIGH
+    // 0x202: LD 0x0 0x70
+    // 0x204: LD 0x1 0x30
+    // 0x206: LD I 0x20C
+    // 0x208: DRW 0x0 0x1 0x0
+    // 0x20A: DRW 0x0 0x1 0x0
+    // 0x20C: 16x16 sprite with only the corners set
+    let mut rom = vec![
+        0x00, 0xFF, 0x60, 0x70, 0x61, 0x30, 0xA2, 0x0C, 0xD0, 0x10, 0xD0, 0x10,
+    ];
+    let mut sprite = [0u8; 32];
+    sprite[0] = 0x80;
+    sprite[1] = 0x01;
+    sprite[30] = 0x80;
+    sprite[31] = 0x01;
+    rom.extend_from_slice(&sprite);
+
+    let mut cpu = super_chip(&rom);
+    for _ in 0..5 {
+        cpu.run_cycle().unwrap();
+    }
+
+    assert_eq!(cpu.gp_reg[0xF], 0);
+    assert_eq!(cpu.display_buffer.pixel(0x70, 0x30), 1);
+    assert_eq!(cpu.display_buffer.pixel(0x7F, 0x30), 1);
+    assert_eq!(cpu.display_buffer.pixel(0x70, 0x3F), 1);
+    assert_eq!(cpu.display_buffer.pixel(0x7F, 0x3F), 1);
+    assert_eq!(
+        cpu.display_buffer
+            .to_pixels()
+            .iter()
+            .filter(|&&p| p == 1)
+            .count(),
+        4
+    );
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0xF], 1);
+    assert!(cpu.display_buffer.to_pixels().iter().all(|&p| p == 0));
+}
+
+#[test]
+fn test_scroll() {
+    // 0x200: LD I 0x000
+    // 0x202: DRW 0x0 0x0 0x1
+    // 0x204: SCD 0x2
+    // 0x206: SCR
+    // 0x208: SCL
+    // 0x20A: SCL
+    const ROM: [u8; 12] = [
+        0xA0, 0x00, 0xD0, 0x01, 0x00, 0xC2, 0x00, 0xFB, 0x00, 0xFC, 0x00, 0xFC,
+    ];
+
+    let mut cpu = super_chip(&ROM);
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_buffer.pixel(0, 0), 1);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_buffer.pixel(0, 0), 0);
+    assert_eq!(cpu.display_buffer.pixel(0, 2), 1);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_buffer.pixel(3, 2), 0);
+    assert_eq!(cpu.display_buffer.pixel(4, 2), 1);
+    assert_eq!(cpu.display_buffer.pixel(7, 2), 1);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_buffer.pixel(0, 2), 1);
+
+    // Pixels scrolled off the edge are lost
+    cpu.run_cycle().unwrap();
+
+    assert!(cpu.display_buffer.to_pixels().iter().all(|&p| p == 0));
+}
+
+#[test]
+fn test_exit() {
+    // 0x200: EXIT
+    // 0x202: LD 0x0 0x01
+    const ROM: [u8; 4] = [0x00, 0xFD, 0x60, 0x01];
+
+    let mut cpu = super_chip(&ROM);
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert!(cpu.is_halted());
+    assert_eq!(cpu.pc, 0x200);
+    assert_eq!(cpu.gp_reg[0x0], 0);
+}
+
+#[test]
+fn test_ld_hf_vx() {
+    // 0x200: LD 0x0 0x9
+    // 0x202: LD HF 0x0
+    const ROM: [u8; 4] = [0x60, 0x09, 0xF0, 0x30];
+    const EXPECTED_I: u32 = BIG_FONT_ADDRESS + 9 * 10;
+
+    let mut cpu = super_chip(&ROM);
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    let sprite_start = cpu.i_reg as usize;
+
+    assert_eq!(cpu.i_reg, EXPECTED_I);
+    assert_eq!(
+        cpu.memory[sprite_start..sprite_start + 10],
+        BIG_FONT[90..100]
+    );
+}
+
+#[test]
+fn test_ld_r_vx_ld_vx_r() {
+    // 0x200: LD 0x0 0x12
+    // 0x202: LD 0x1 0x34
+    // 0x204: LD R 0x1
+    // 0x206: LD 0x0 0x00
+    // 0x208: LD 0x1 0x00
+    // 0x20A: LD 0x1 R
+    const ROM: [u8; 12] = [
+        0x60, 0x12, 0x61, 0x34, 0xF1, 0x75, 0x60, 0x00, 0x61, 0x00, 0xF1, 0x85,
+    ];
+
+    let mut cpu = super_chip(&ROM);
+    for _ in 0..3 {
+        cpu.run_cycle().unwrap();
+    }
+
+    assert_eq!(cpu.rpl_flags[0..2], [0x12, 0x34]);
+
+    for _ in 0..3 {
+        cpu.run_cycle().unwrap();
+    }
+
+    assert_eq!(cpu.gp_reg[0..2], [0x12, 0x34]);
+}
@@ -0,0 +1,188 @@
+use chip8::cpu::{BlockRun, Cpu, Engine};
+use chip8::error::Chip8Error;
+use chip8::machine::{MachineConfig, MemoryLayout};
+use chip8::platform::Platform;
+use chip8::timing::TimingMode;
+use chip8::Chip8;
+
+// 0x200: LD V0 0x05
+// 0x202: RND V1 0xFF
+// 0x204: ADD V0 V1
+// 0x206: SHR V2 V0
+// 0x208: LD F V0
+// 0x20A: DRW V1 V2 0x5
+// 0x20C: CALL 0x214
+// 0x20E: SE V0 0x00
+// 0x210: JP 0x202
+// 0x212: JP 0x200
+// 0x214: LD I 0x300
+// 0x216: LD B V1
+// 0x218: LD V2 [I]
+// 0x21A: RET
+const MIXED_ROM: [u8; 28] = [
+    0x60, 0x05, 0xC1, 0xFF, 0x80, 0x14, 0x82, 0x06, 0xF0, 0x29, 0xD1, 0x25, 0x22, 0x14, 0x30, 0x00,
+    0x12, 0x02, 0x12, 0x00, 0xA3, 0x00, 0xF1, 0x33, 0xF2, 0x65, 0x00, 0xEE,
+];
+
+/// Run blocks on one CPU and as many interpreter steps on a copy, checking
+/// both agree after every block
+fn assert_lockstep(rom: &[u8], config: MachineConfig, blocks: usize) -> Cpu {
+    let mut threaded = Cpu::new(rom, config);
+    let mut interpreted = threaded.clone();
+
+    for _ in 0..blocks {
+        let run = threaded.run_block(u32::MAX, u32::MAX).unwrap();
+
+        let mut cycles = 0;
+        for _ in 0..run.instructions {
+            cycles += interpreted.step().unwrap();
+        }
+
+        assert_eq!(run.cycles, cycles);
+        assert_eq!(threaded, interpreted);
+    }
+
+    threaded
+}
+
+#[test]
+fn test_lockstep_mixed() {
+    let config = MachineConfig {
+        rng_seed: 1234,
+        ..MachineConfig::default()
+    };
+
+    assert_lockstep(&MIXED_ROM, config, 200);
+}
+
+#[test]
+fn test_lockstep_self_modifying_code() {
+    // 0x200: LD V0 0x01
+    // 0x202: ADD V0 0x07
+    // 0x204: LD I 0x201
+    // 0x206: LD [I] V0
+    // 0x208: JP 0x200
+    const ROM: [u8; 10] = [0x60, 0x01, 0x70, 0x07, 0xA2, 0x01, 0xF0, 0x55, 0x12, 0x00];
+
+    let cpu = assert_lockstep(&ROM, MachineConfig::default(), 3);
+
+    // The block at 0x200 was recompiled after LD [I] V0 rewrote it to LD V0 0x08
+    assert_eq!(cpu.gp_reg[0x0], 0x0F);
+    assert_eq!(cpu.memory[0x201], 0x0F);
+}
+
+#[test]
+fn test_block_instruction_limit() {
+    // 0x200: LD V0 0x01
+    // 0x202: LD V1 0x02
+    // 0x204: LD V2 0x03
+    // 0x206: JP 0x200
+    const ROM: [u8; 8] = [0x60, 0x01, 0x61, 0x02, 0x62, 0x03, 0x12, 0x00];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+
+    let run = cpu.run_block(2, u32::MAX).unwrap();
+
+    assert_eq!(
+        run,
+        BlockRun {
+            instructions: 2,
+            cycles: 2 * (40 + 6)
+        }
+    );
+    assert_eq!(cpu.pc, 0x204);
+
+    // The block resumes in the middle of the previous one
+    let run = cpu.run_block(u32::MAX, 1).unwrap();
+
+    assert_eq!(run.instructions, 1);
+    assert_eq!(cpu.pc, 0x206);
+    assert_eq!(cpu.gp_reg[0x2], 0x03);
+
+    let run = cpu.run_block(u32::MAX, u32::MAX).unwrap();
+
+    assert_eq!(run.instructions, 1);
+    assert_eq!(cpu.pc, 0x200);
+}
+
+#[test]
+fn test_block_error_matches_interpreter() {
+    // 0x200: LD V0 0x01
+    // 0x202: LD I 0xFFF
+    // 0x204: LD V1 [I]
+    const ROM: [u8; 6] = [0x60, 0x01, 0xAF, 0xFF, 0xF1, 0x65];
+
+    let mut threaded = Cpu::new(&ROM, MachineConfig::default());
+    let mut interpreted = threaded.clone();
+
+    let threaded_err = threaded.run_block(u32::MAX, u32::MAX).err();
+    interpreted.step().unwrap();
+    interpreted.step().unwrap();
+    let interpreted_err = interpreted.step().err();
+
+    assert!(matches!(
+        threaded_err,
+        Some(Chip8Error::MemoryOutOfBounds { .. })
+    ));
+    assert_eq!(threaded_err, interpreted_err);
+    assert_eq!(threaded, interpreted);
+}
+
+#[test]
+fn test_vip_layout_falls_back_to_interpreter() {
+    // 0x200: LD V0 0x01
+    // 0x202: LD V1 0x02
+    const ROM: [u8; 4] = [0x60, 0x01, 0x61, 0x02];
+
+    let config = MachineConfig {
+        memory_layout: MemoryLayout::Vip,
+        ..MachineConfig::default()
+    };
+    let mut cpu = Cpu::new(&ROM, config);
+
+    let run = cpu.run_block(u32::MAX, u32::MAX).unwrap();
+
+    assert_eq!(run.instructions, 1);
+    assert_eq!(cpu.pc, 0x202);
+}
+
+#[test]
+fn test_engines_run_same_frames() {
+    for timing in [
+        TimingMode::InstructionsPerFrame(15),
+        TimingMode::CycleAccurate,
+    ] {
+        let mut interpreted = Chip8::new(&MIXED_ROM, Platform::Cowgod);
+        interpreted.set_timing(timing);
+        let mut threaded = Chip8::new(&MIXED_ROM, Platform::Cowgod);
+        threaded.set_timing(timing);
+        threaded.set_engine(Engine::Threaded);
+
+        for _ in 0..60 {
+            let expected = interpreted.run_frame().unwrap();
+            let frame = threaded.run_frame().unwrap();
+
+            assert_eq!(frame.sound_event, expected.sound_event);
+            assert_eq!(threaded.cpu(), interpreted.cpu());
+        }
+    }
+}
+
+fn assert_send<T: Send>() {}
+
+#[test]
+fn test_compiled_blocks_are_send() {
+    assert_send::<Cpu>();
+    assert_send::<Chip8>();
+
+    let mut chip8 = Chip8::new(&MIXED_ROM, Platform::Cowgod);
+    chip8.set_engine(Engine::Threaded);
+    chip8.run_frame().unwrap();
+
+    // Cached blocks move with the emulator to another thread
+    let worker = std::thread::spawn(move || {
+        chip8.run_frame().unwrap();
+        chip8
+    });
+    assert!(worker.join().unwrap().cpu().is_running());
+}
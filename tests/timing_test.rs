@@ -0,0 +1,148 @@
+use chip8::cpu::Cpu;
+use chip8::machine::MachineConfig;
+use chip8::platform::Platform;
+use chip8::state::SoundEvent;
+use chip8::timing::{TimingMode, VIP_FRAME_CYCLES};
+use chip8::Chip8;
+
+/// ROM of ADD V0 0x01 repeated, 50 machine cycles each
+fn add_loop() -> Vec<u8> {
+    [0x70, 0x01].repeat(200)
+}
+
+#[test]
+fn test_step_cycles() {
+    // 0x200: LD V0 0x01
+    // 0x202: SE V0 0x01
+    // 0x204: (skipped)
+    // 0x206: SE V0 0x02
+    // 0x208: ADD V0 V0
+    const ROM: [u8; 10] = [0x60, 0x01, 0x30, 0x01, 0x00, 0x00, 0x30, 0x02, 0x80, 0x04];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+
+    assert_eq!(cpu.step().unwrap(), 46);
+    assert_eq!(cpu.step().unwrap(), 54);
+    assert_eq!(cpu.step().unwrap(), 50);
+    assert_eq!(cpu.step().unwrap(), 84);
+}
+
+#[test]
+fn test_drw_cycles() {
+    // 0x200: DRW V0 V0 5
+    // 0x202: DRW V1 V0 5
+    const ROM: [u8; 4] = [0xD0, 0x05, 0xD1, 0x05];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+    cpu.gp_reg[0x1] = 3;
+
+    assert_eq!(cpu.step().unwrap(), 40 + 26 + 5 * 46);
+    assert_eq!(cpu.step().unwrap(), 40 + 26 + 5 * (46 + 3 * 4));
+}
+
+#[test]
+fn test_step_while_halted() {
+    // 0x200: LD V0 K
+    const ROM: [u8; 2] = [0xF0, 0x0A];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+
+    assert!(cpu.step().unwrap() > 0);
+    assert_eq!(cpu.step().unwrap(), 0);
+}
+
+#[test]
+fn test_run_frame_instructions_per_frame() {
+    let mut chip8 = Chip8::new(&add_loop(), Platform::Cowgod);
+
+    assert_eq!(chip8.timing(), TimingMode::InstructionsPerFrame(10));
+
+    chip8.run_frame().unwrap();
+
+    assert_eq!(chip8.cpu().gp_reg[0x0], 10);
+}
+
+#[test]
+fn test_run_frame_cycle_accurate() {
+    let mut chip8 = Chip8::new(&add_loop(), Platform::Cowgod);
+    chip8.set_timing(TimingMode::CycleAccurate);
+
+    assert_eq!(VIP_FRAME_CYCLES, 2584);
+
+    chip8.run_frame().unwrap();
+
+    // The last instruction overruns the frame by 16 cycles
+    assert_eq!(chip8.cpu().gp_reg[0x0], 52);
+
+    chip8.run_frame().unwrap();
+
+    // Which are taken from the next frame, leaving 2568 cycles
+    assert_eq!(chip8.cpu().gp_reg[0x0], 104);
+}
+
+#[test]
+fn test_run_frame_ends_on_display_wait() {
+    // 0x200: DRW V0 V0 1
+    let mut rom = vec![0xD0, 0x01];
+    rom.extend(add_loop());
+
+    let mut chip8 = Chip8::new(&rom, Platform::CosmacVip);
+    chip8.set_timing(TimingMode::CycleAccurate);
+
+    let frame = chip8.run_frame().unwrap();
+
+    assert!(frame.state.draw_flag);
+    assert_eq!(chip8.cpu().pc, 0x202);
+
+    chip8.run_frame().unwrap();
+
+    assert!(chip8.cpu().gp_reg[0x0] > 0);
+}
+
+#[test]
+fn test_run_frame_ticks_timers() {
+    // 0x200: LD V0 0x02
+    // 0x202: LD ST V0
+    // 0x204: JP 0x204
+    const ROM: [u8; 6] = [0x60, 0x02, 0xF0, 0x18, 0x12, 0x04];
+
+    let mut chip8 = Chip8::new(&ROM, Platform::Cowgod);
+
+    let frame = chip8.run_frame().unwrap();
+
+    assert_eq!(frame.sound_event, Some(SoundEvent::Start));
+    assert_eq!(chip8.cpu().sound_timer, 1);
+
+    let frame = chip8.run_frame().unwrap();
+
+    assert_eq!(frame.sound_event, None);
+
+    let frame = chip8.run_frame().unwrap();
+
+    assert_eq!(frame.sound_event, Some(SoundEvent::Stop));
+}
+
+#[test]
+fn test_tick_rate_presets() {
+    let mut chip8 = Chip8::new(&add_loop(), Platform::Cowgod);
+
+    assert_eq!(chip8.tick_rate(), Some(10));
+    assert_eq!(chip8.increase_tick_rate(), 15);
+    assert_eq!(chip8.increase_tick_rate(), 20);
+    assert_eq!(chip8.decrease_tick_rate(), 15);
+    assert_eq!(chip8.decrease_tick_rate(), 7);
+    assert_eq!(chip8.decrease_tick_rate(), 7);
+
+    chip8.run_frame().unwrap();
+
+    assert_eq!(chip8.cpu().gp_reg[0x0], 7);
+
+    chip8.set_timing(TimingMode::CycleAccurate);
+
+    assert_eq!(chip8.tick_rate(), None);
+    assert_eq!(chip8.increase_tick_rate(), 7);
+
+    chip8.set_tick_rate(1000);
+
+    assert_eq!(chip8.increase_tick_rate(), 1000);
+}
@@ -0,0 +1,121 @@
+use chip8::cpu::Cpu;
+use chip8::error::Chip8Error;
+use chip8::machine::{MachineConfig, MemoryLayout};
+
+fn vip_layout(rom: &[u8]) -> Cpu {
+    let config = MachineConfig {
+        memory_layout: MemoryLayout::Vip,
+        ..MachineConfig::default()
+    };
+    Cpu::new(rom, config)
+}
+
+#[test]
+fn test_layout_addresses() {
+    const ROM: [u8; 2] = [0x00, 0xE0];
+
+    let cpu = vip_layout(&ROM);
+
+    assert_eq!(cpu.memory_layout(), MemoryLayout::Vip);
+    assert_eq!(cpu.stack_address(), Some(0xEA0));
+    assert_eq!(cpu.register_address(), Some(0xEF0));
+    assert_eq!(cpu.display_address(), Some(0xF00));
+
+    let cpu = Cpu::new(&ROM, MachineConfig::default());
+
+    assert_eq!(cpu.memory_layout(), MemoryLayout::Separate);
+    assert_eq!(cpu.register_address(), None);
+}
+
+#[test]
+fn test_registers_in_memory() {
+    // 0x200: LD V3 0x42
+    // 0x202: LD I 0xEF5
+    // 0x204: LD [I] V0
+    const ROM: [u8; 6] = [0x63, 0x42, 0xAE, 0xF5, 0xF0, 0x55];
+
+    let mut cpu = vip_layout(&ROM);
+    cpu.gp_reg[0x0] = 0x24;
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.memory[0xEF0], 0x24);
+    assert_eq!(cpu.memory[0xEF3], 0x42);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x5], 0x24);
+    assert_eq!(cpu.memory[0xEF5], 0x24);
+}
+
+#[test]
+fn test_stack_in_memory() {
+    // 0x200: CALL 0x204
+    // 0x202: DUMMY INSTRUCTION
+    // 0x204: LD I 0xEA0
+    // 0x206: LD V0 0x02
+    // 0x208: LD V1 0x0A
+    // 0x20A: LD [I] V1
+    // 0x20C: RET
+    const ROM: [u8; 14] = [
+        0x22, 0x04, 0x00, 0x00, 0xAE, 0xA0, 0x60, 0x02, 0x61, 0x0A, 0xF1, 0x55, 0x00, 0xEE,
+    ];
+
+    let mut cpu = vip_layout(&ROM);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.memory[0xEA0..0xEA2], [0x02, 0x02]);
+
+    for _ in 0..5 {
+        cpu.run_cycle().unwrap();
+    }
+
+    assert_eq!(cpu.pc, 0x20A);
+}
+
+#[test]
+fn test_display_in_memory() {
+    // 0x200: LD I 0x000
+    // 0x202: DRW V0 V0 1
+    // 0x204: LD I 0xF08
+    // 0x206: LD [I] V0
+    const ROM: [u8; 8] = [0xA0, 0x00, 0xD0, 0x01, 0xAF, 0x08, 0xF0, 0x55];
+
+    let mut cpu = vip_layout(&ROM);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.memory[0xF00], 0xF0);
+
+    cpu.gp_reg[0x0] = 0x81;
+    cpu.run_cycle().unwrap();
+    let state = cpu.run_cycle().unwrap();
+
+    assert!(state.draw_flag);
+    assert_eq!(state.display_buffer.pixel(0, 1), 1);
+    assert_eq!(state.display_buffer.pixel(1, 1), 0);
+    assert_eq!(state.display_buffer.pixel(7, 1), 1);
+}
+
+#[test]
+fn test_rom_must_not_overlap_layout() {
+    const ROM: [u8; 0xCA1] = [0; 0xCA1];
+
+    let config = MachineConfig {
+        memory_layout: MemoryLayout::Vip,
+        ..MachineConfig::default()
+    };
+
+    assert_eq!(
+        Cpu::try_new(&ROM, config).err(),
+        Some(Chip8Error::RomTooLarge {
+            address: 0x200,
+            size: 0xCA1,
+            max_size: 0xCA0
+        })
+    );
+    assert!(Cpu::try_new(&ROM[..0xCA0], config).is_ok());
+}
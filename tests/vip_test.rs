@@ -0,0 +1,161 @@
+use chip8::cpu::Cpu;
+use chip8::error::Chip8Error;
+use chip8::machine::{MachineConfig, MemoryLayout};
+use chip8::vip::Vip;
+
+fn vip_layout(rom: &[u8]) -> Cpu {
+    let config = MachineConfig {
+        memory_layout: MemoryLayout::Vip,
+        ..MachineConfig::default()
+    };
+    Cpu::new(rom, config)
+}
+
+#[test]
+fn test_pixie_display_dma() {
+    // 0x000: R1 = 0x0040 (interrupt routine)
+    // 0x006: R2 = 0x01F0 (stack)
+    // 0x00C: R3 = 0x0012, SEP R3
+    // 0x012: SEX R2
+    // 0x013: INP 1 (display on)
+    // 0x014: BR 0x14
+    // 0x03F: RET
+    // 0x040: DEC R2, SAV
+    // 0x042: R0 = 0x0300 (display buffer)
+    // 0x048: BR 0x3F
+    let mut interpreter = vec![
+        0xF8, 0x00, 0xB1, 0xF8, 0x40, 0xA1, 0xF8, 0x01, 0xB2, 0xF8, 0xF0, 0xA2, 0xF8, 0x12, 0xA3,
+        0xD3, 0x00, 0x00, 0xE2, 0x69, 0x30, 0x14,
+    ];
+    interpreter.resize(0x3F, 0x00);
+    interpreter.extend([
+        0x70, 0x22, 0x78, 0xF8, 0x03, 0xB0, 0xF8, 0x00, 0xA0, 0x30, 0x3F,
+    ]);
+
+    // 0x300: first two display lines
+    let mut program = vec![0x00; 0x100];
+    program.extend([0x80, 0, 0, 0, 0, 0, 0, 0x01, 0xFF]);
+
+    let mut vip = Vip::new(&interpreter, &program).unwrap();
+
+    let state = vip.run_frame();
+
+    assert!(vip.pixie().is_enabled());
+    assert!(state.draw_flag);
+    assert_eq!(state.display_buffer.width(), 64);
+    assert_eq!(state.display_buffer.height(), 128);
+    assert_eq!(state.display_buffer.pixel(0, 0), 1);
+    assert_eq!(state.display_buffer.pixel(1, 0), 0);
+    assert_eq!(state.display_buffer.pixel(63, 0), 1);
+    assert_eq!(state.display_buffer.pixel(7, 1), 1);
+    assert_eq!(state.display_buffer.pixel(8, 1), 0);
+    assert_eq!(vip.cpu().r[0], 0x700);
+
+    let state = vip.run_frame();
+
+    assert!(!state.draw_flag);
+}
+
+#[test]
+fn test_keypad_latch() {
+    // 0x000: R2 = 0x01F0
+    // 0x006: LDI 0x0A, STR R2
+    // 0x009: SEX R2
+    // 0x00A: OUT 2 (select key A)
+    // 0x00B: DEC R2
+    // 0x00C: B3 0x10
+    // 0x00E: BR 0x0A
+    // 0x010: SEQ
+    // 0x011: BR 0x11
+    const INTERPRETER: [u8; 19] = [
+        0xF8, 0x01, 0xB2, 0xF8, 0xF0, 0xA2, 0xF8, 0x0A, 0x52, 0xE2, 0x62, 0x22, 0x36, 0x10, 0x30,
+        0x0A, 0x7B, 0x30, 0x11,
+    ];
+
+    let mut vip = Vip::new(&INTERPRETER, &[]).unwrap();
+
+    vip.key_down(0xB);
+    vip.run_frame();
+
+    assert!(!vip.is_sound_playing());
+
+    vip.key_down(0xA);
+    vip.run_frame();
+
+    assert!(vip.is_sound_playing());
+}
+
+#[test]
+fn test_interpreter_entry_registers() {
+    let vip = Vip::new(&[0x30, 0x00], &[0x12, 0x00]).unwrap();
+
+    assert_eq!(vip.cpu().r[1], 0x0F00);
+    assert_eq!(vip.memory()[0x200..0x202], [0x12, 0x00]);
+}
+
+#[test]
+fn test_interpreter_too_large() {
+    assert_eq!(
+        Vip::new(&[0; 0x201], &[]).err(),
+        Some(Chip8Error::RomTooLarge {
+            address: 0x000,
+            size: 0x201,
+            max_size: 0x200
+        })
+    );
+}
+
+#[test]
+fn test_sys_machine_code() {
+    // 0x200: LD V0 0x05
+    // 0x202: SYS 0x206
+    // 0x204: JP 0x204
+    // 0x206: LDA R7 (V0)
+    // 0x207: ADI 0x03
+    // 0x209: STR R6 (V2)
+    // 0x20A: SEP R4
+    const ROM: [u8; 11] = [
+        0x60, 0x05, 0x02, 0x06, 0x12, 0x04, 0x47, 0xFC, 0x03, 0x56, 0xD4,
+    ];
+
+    let mut cpu = vip_layout(&ROM);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[0x2], 0x08);
+    assert_eq!(cpu.pc, 0x204);
+}
+
+#[test]
+fn test_sys_requires_vip_layout() {
+    // 0x200: SYS 0x206
+    const ROM: [u8; 2] = [0x02, 0x06];
+
+    let mut cpu = Cpu::new(&ROM, MachineConfig::default());
+
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::UnknownOpcode {
+            address: 0x200,
+            opcode: 0x0206
+        })
+    );
+}
+
+#[test]
+fn test_sys_machine_code_limit() {
+    // 0x200: SYS 0x202
+    // 0x202: BR 0x02
+    const ROM: [u8; 4] = [0x02, 0x02, 0x30, 0x02];
+
+    let mut cpu = vip_layout(&ROM);
+
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::MachineCodeLimit {
+            address: 0x200,
+            opcode: 0x0202
+        })
+    );
+}
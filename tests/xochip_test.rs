@@ -0,0 +1,209 @@
+use chip8::cpu::Cpu;
+use chip8::error::Chip8Error;
+use chip8::platform::Platform;
+use chip8::state::AudioPattern;
+use chip8::Chip8;
+
+fn xo_chip(rom: &[u8]) -> Cpu {
+    Cpu::new(rom, Platform::XoChip.machine_config())
+}
+
+#[test]
+fn test_xo_chip_not_decoded_for_super_chip() {
+    // 0x200: LD I 0x1234 (long)
+    const ROM: [u8; 4] = [0xF0, 0x00, 0x12, 0x34];
+
+    let mut cpu = Cpu::new(&ROM, Platform::SuperChip11.machine_config());
+
+    assert_eq!(
+        cpu.run_cycle().err(),
+        Some(Chip8Error::UnknownOpcode {
+            address: 0x200,
+            opcode: 0xF000
+        })
+    );
+}
+
+#[test]
+fn test_ld_i_long() {
+    // 0x200: LD I 0xFFF0 (long)
+    // 0x204: LD V0 0xAB
+    // 0x206: LD [I] V0
+    const ROM: [u8; 8] = [0xF0, 0x00, 0xFF, 0xF0, 0x60, 0xAB, 0xF0, 0x55];
+
+    let mut cpu = xo_chip(&ROM);
+
+    assert_eq!(cpu.memory.len(), 0x10000);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.i_reg, 0xFFF0);
+    assert_eq!(cpu.pc, 0x204);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.memory[0xFFF0], 0xAB);
+}
+
+#[test]
+fn test_skip_over_ld_i_long() {
+    // 0x200: SE V0 0x00
+    // 0x202: LD I 0x1234 (long)
+    // 0x206: SNE V0 0x00
+    const ROM: [u8; 8] = [0x30, 0x00, 0xF0, 0x00, 0x12, 0x34, 0x40, 0x00];
+
+    let mut cpu = xo_chip(&ROM);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x206);
+    assert_eq!(cpu.i_reg, 0);
+}
+
+#[test]
+fn test_save_load_vx_vy() {
+    // 0x200: LD I 0x300
+    // 0x202: SAVE V1 - V3
+    // 0x204: LOAD V6 - V4
+    const ROM: [u8; 6] = [0xA3, 0x00, 0x51, 0x32, 0x56, 0x43];
+
+    let mut cpu = xo_chip(&ROM);
+    cpu.gp_reg[1..4].copy_from_slice(&[0x11, 0x22, 0x33]);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.memory[0x300..0x303], [0x11, 0x22, 0x33]);
+    assert_eq!(cpu.i_reg, 0x300);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.gp_reg[4..7], [0x33, 0x22, 0x11]);
+}
+
+#[test]
+fn test_plane_drawing() {
+    // 0x200: PLANE 3
+    // 0x202: LD I 0x20A
+    // 0x204: DRW V0 V0 1
+    // 0x206: PLANE 2
+    // 0x208: CLS
+    // 0x20A: sprite data for plane 1 then plane 2
+    const ROM: [u8; 12] = [
+        0xF3, 0x01, 0xA2, 0x0A, 0xD0, 0x01, 0xF2, 0x01, 0x00, 0xE0, 0xC0, 0x80,
+    ];
+
+    let mut cpu = xo_chip(&ROM);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+    let state = cpu.run_cycle().unwrap();
+
+    assert_eq!(state.display_buffer.pixel(0, 0), 0b11);
+    assert_eq!(state.display_buffer.pixel(1, 0), 0b01);
+    assert_eq!(state.display_buffer.pixel(2, 0), 0b00);
+    assert_eq!(cpu.gp_reg[0xF], 0);
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.display_buffer.pixel(0, 0), 0b01);
+    assert_eq!(cpu.display_buffer.pixel(1, 0), 0b01);
+}
+
+#[test]
+fn test_scroll_up_selected_plane() {
+    // 0x200: PLANE 3
+    // 0x202: LD I 0x20A
+    // 0x204: DRW V0 V1 1
+    // 0x206: PLANE 1
+    // 0x208: SCU 1
+    // 0x20A: sprite data for plane 1 then plane 2
+    const ROM: [u8; 12] = [
+        0xF3, 0x01, 0xA2, 0x0A, 0xD0, 0x11, 0xF1, 0x01, 0x00, 0xD1, 0x80, 0x80,
+    ];
+
+    let mut cpu = xo_chip(&ROM);
+    cpu.gp_reg[1] = 1;
+
+    for _ in 0..5 {
+        cpu.run_cycle().unwrap();
+    }
+
+    assert_eq!(cpu.display_buffer.pixel(0, 0), 0b01);
+    assert_eq!(cpu.display_buffer.pixel(0, 1), 0b10);
+}
+
+#[test]
+fn test_audio_pattern_and_pitch() {
+    // 0x200: LD I 0x208
+    // 0x202: AUDIO
+    // 0x204: PITCH V0
+    // 0x206: JP 0x206
+    // 0x208: audio pattern
+    let mut rom = vec![0xA2, 0x08, 0xF0, 0x02, 0xF0, 0x3A, 0x12, 0x06];
+    rom.extend((0..16).map(|byte| byte as u8));
+
+    let mut chip8 = Chip8::new(&rom, Platform::XoChip);
+
+    assert_eq!(chip8.audio_pattern(), None);
+
+    chip8.run_cycle().unwrap();
+    chip8.run_cycle().unwrap();
+    chip8.run_cycle().unwrap();
+
+    let pattern = chip8.audio_pattern().unwrap();
+    assert_eq!(pattern.pattern[15], 15);
+    assert_eq!(pattern.pitch, 0);
+    assert!(pattern.bit(15 * 8 + 4));
+    assert!(!pattern.bit(15 * 8 + 3));
+}
+
+#[test]
+fn test_audio_pattern_playback_rate() {
+    let pattern = AudioPattern {
+        pattern: [0; 16],
+        pitch: 64,
+    };
+    assert_eq!(pattern.playback_rate(), 4000.0);
+
+    let pattern = AudioPattern {
+        pattern: [0; 16],
+        pitch: 112,
+    };
+    assert_eq!(pattern.playback_rate(), 8000.0);
+}
+
+#[test]
+fn test_pc_wraps_at_end_of_memory() {
+    let mut cpu = xo_chip(&[]);
+
+    // 0xFFFC: LD V0 0x01
+    // 0xFFFE: CALL 0x300
+    // 0x0300: RET
+    cpu.memory[0xFFFC..].copy_from_slice(&[0x60, 0x01, 0x23, 0x00]);
+    cpu.memory[0x300..0x302].copy_from_slice(&[0x00, 0xEE]);
+    cpu.invalidate_decode_cache();
+    cpu.pc = 0xFFFC;
+
+    cpu.run_cycle().unwrap();
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.call_stack(), [0x0000]);
+
+    cpu.run_cycle().unwrap();
+
+    assert_eq!(cpu.pc, 0x0000);
+
+    // Compiled blocks end at the top of memory
+    cpu.memory[0xFFFE..].copy_from_slice(&[0x60, 0x02]);
+    cpu.invalidate_decode_cache();
+    cpu.pc = 0xFFFC;
+
+    let run = cpu.run_block(u32::MAX, u32::MAX).unwrap();
+
+    assert_eq!(run.instructions, 2);
+    assert_eq!(cpu.gp_reg[0x0], 0x02);
+    assert_eq!(cpu.pc, 0x0000);
+}